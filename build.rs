@@ -2,6 +2,10 @@ fn main() {
     use std::path::PathBuf;
     use std::env;
 
+    if env::var_os("CARGO_FEATURE_TRACE").is_some() {
+        trace::generate();
+    }
+
     let triple = env::var("TARGET").unwrap();
     let triple = triple.split('-').collect::<Vec<_>>();
     let arch = &*triple[0];
@@ -21,4 +25,13 @@ fn main() {
         println!("cargo:rustc-link-search=native={}", path.display());
         println!("cargo:rustc-link-lib=static={}", lib_name);
     }
-}
\ No newline at end of file
+}
+
+/// Generates the call-tracing wrappers of the `trace` feature.
+///
+/// Every `pub fn` in the `extern "C"` blocks of a source file is moved into a private `raw` module
+/// and replaced by a `pub unsafe extern "C" fn` of the same name and signature that reports the
+/// call to `::trace::call`, so that the wrappers can still be used as C function pointers. The
+/// blocks themselves are disabled with `#[cfg(not(feature = "trace"))]`.
+#[path = "build/trace.rs"]
+mod trace;
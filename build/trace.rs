@@ -0,0 +1,244 @@
+use std::env;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{Read, Write as IoWrite};
+use std::path::Path;
+
+const SOURCES: &[(&str, &str)] = &[
+    ("src/lib.rs", "trace.rs"),
+    ("src/opengl.rs", "trace_opengl.rs"),
+    ("src/vulkan.rs", "trace_vulkan.rs"),
+    ("src/audio.rs", "trace_audio.rs"),
+];
+
+struct Function {
+    docs: Vec<String>,
+    name: String,
+    arguments: Vec<(String, String)>,
+    ret: Option<String>,
+}
+
+pub fn generate() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    for &(source, output) in SOURCES {
+        println!("cargo:rerun-if-changed={}", source);
+        let mut text = String::new();
+        File::open(source).unwrap().read_to_string(&mut text).unwrap();
+        let functions = parse(&text);
+        let mut file = File::create(Path::new(&out_dir).join(output)).unwrap();
+        file.write_all(render(&functions, source == "src/lib.rs").as_bytes()).unwrap();
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=build/trace.rs");
+}
+
+fn parse(text: &str) -> Vec<Function> {
+    let mut functions = Vec::new();
+    let mut in_block = false;
+    let mut docs = Vec::new();
+    let mut signature = String::new();
+    for line in text.lines() {
+        if !in_block {
+            in_block = line.starts_with("extern \"C\" {");
+            continue;
+        }
+        if line.starts_with('}') {
+            in_block = false;
+            docs.clear();
+            continue;
+        }
+        let line = line.trim();
+        if !signature.is_empty() || line.starts_with("pub fn ") {
+            signature.push_str(line);
+            signature.push(' ');
+            if line.ends_with(';') {
+                functions.push(parse_signature(&signature, docs.split_off(0)));
+                signature.clear();
+            }
+        } else if line.starts_with("///") {
+            docs.push(line.to_string());
+        }
+    }
+    functions
+}
+
+fn parse_signature(signature: &str, docs: Vec<String>) -> Function {
+    let signature = signature.trim().trim_start_matches("pub fn ").trim_end_matches(';');
+    let open = signature.find('(').unwrap();
+    let close = signature.rfind(')').unwrap();
+    let arguments = signature[open + 1..close].split(',')
+        .filter(|argument| !argument.trim().is_empty())
+        .map(|argument| {
+            let colon = argument.find(':').unwrap();
+            (argument[..colon].trim().to_string(), normalize(&argument[colon + 1..]))
+        })
+        .collect();
+    let ret = signature[close + 1..].trim();
+    Function {
+        docs,
+        name: signature[..open].trim().to_string(),
+        arguments,
+        ret: if ret.is_empty() { None } else { Some(normalize(ret.trim_start_matches("->"))) },
+    }
+}
+
+fn normalize(ty: &str) -> String {
+    ty.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// How an argument is reported: pointers to strings are read as strings, pointers to inputs are
+/// read before the call, and pointers to outputs are read after it.
+///
+/// Some outputs are declared `*const`, like the `outEyePoses` of `ovr_GetEyePoses`, and some
+/// inputs `*mut`, like the clip of `ovr_ReleaseHapticsClip`. The direction documented as `**in**`
+/// or `**out**` is used when there is one, then an `out` prefix in the name marks an output, and
+/// otherwise `*mut` pointers are outputs.
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Value,
+    CString,
+    Input,
+    Output,
+}
+
+fn kind(function: &Function, name: &str, ty: &str) -> Kind {
+    let opaque = |pointee: &str| pointee.starts_with('*') || pointee == "c_void" || pointee == "WCHAR";
+    let pointee = ty.strip_prefix("*const ").or_else(|| ty.strip_prefix("*mut "));
+    let documented = |direction: &str| {
+        let marker = format!("**{}** `{}`", direction, name);
+        function.docs.iter().any(|doc| doc.trim_start_matches('/').trim().starts_with(&marker))
+    };
+    let named_output = name.starts_with("out") && name[3..].starts_with(|c: char| c.is_ascii_uppercase() || c == '_');
+    match pointee {
+        _ if ty == "*const c_char" => Kind::CString,
+        Some(pointee) if opaque(pointee) => Kind::Value,
+        Some(_) if documented("out") => Kind::Output,
+        Some(_) if documented("in") => Kind::Input,
+        Some(_) if named_output || ty.starts_with("*mut ") => Kind::Output,
+        Some(_) => Kind::Input,
+        None => Kind::Value,
+    }
+}
+
+/// When the outputs of a function are written, and may be read after the call. Outputs may not be
+/// initialized otherwise.
+#[derive(Debug, PartialEq)]
+enum Written {
+    /// Functions returning nothing cannot fail, and always write their outputs.
+    Always,
+    /// Outputs are written when the condition on the `result` of the call holds.
+    If(&'static str),
+    /// There is no telling whether the outputs were written, so they are not read.
+    Unknown,
+}
+
+fn outputs_written(function: &Function) -> Written {
+    match function.ret.as_deref() {
+        None => Written::Always,
+        Some("ovrResult") => Written::If("::OVR_SUCCESS(*result)"),
+        // Such functions, like `ovr_GetFloatArray`, return the number of elements written.
+        Some("c_int") | Some("c_uint") => Written::If("*result > 0"),
+        Some(_) => Written::Unknown,
+    }
+}
+
+fn render(functions: &[Function], root: bool) -> String {
+    let link = if root { "trace/index.html" } else { "../trace/index.html" };
+    let mut out = String::new();
+    out.push_str("mod raw {\n    use super::*;\n\n    extern \"C\" {\n");
+    for function in functions {
+        writeln!(out, "        pub fn {}({}){};", function.name, parameters(function), ret(function)).unwrap();
+    }
+    out.push_str("    }\n}\n");
+    for function in functions {
+        out.push('\n');
+        for doc in &function.docs {
+            writeln!(out, "{}", doc).unwrap();
+        }
+        if !function.docs.is_empty() {
+            out.push_str("///\n");
+        }
+        writeln!(out, "/// Traced by the `trace` feature, see the [`trace`]({}) module.", link).unwrap();
+        // The docs are copied from the declarations, and have no safety section.
+        out.push_str("#[allow(clippy::missing_safety_doc, clippy::doc_overindented_list_items)]\n");
+        writeln!(out, "pub unsafe extern \"C\" fn {}({}){} {{", function.name, parameters(function), ret(function)).unwrap();
+        let names = function.arguments.iter().map(|a| &*a.0).collect::<Vec<_>>().join(", ");
+        let written = outputs_written(function);
+        let indent = if let Written::If(_) = written { "            " } else { "        " };
+        let mut before = String::new();
+        let mut after = String::new();
+        for (name, ty) in &function.arguments {
+            let value = match kind(function, name, ty) {
+                Kind::CString => format!("::trace::c_str({})", name),
+                Kind::Input => format!("::trace::pointee({})", name),
+                Kind::Value | Kind::Output => format!("::trace::value(&{})", name),
+            };
+            writeln!(before, "        call.argument(\"{}\", {});", name, value).unwrap();
+            if let Kind::Output = kind(function, name, ty) {
+                writeln!(after, "{}call.output(\"{}\", ::trace::pointee({}));", indent, name, name).unwrap();
+            }
+        }
+        let result = match function.ret.as_deref() {
+            Some("*const c_char") => "::trace::c_str(*result)",
+            _ => "::trace::value(result)",
+        };
+        let recorded = function.ret.as_deref() == Some("ovrResult");
+        let call = if recorded { "    let result = ::trace::call" } else { "    ::trace::call" };
+        writeln!(out, "{}(\"{}\", |{}| {{", call, function.name, if before.is_empty() { "_" } else { "call" }).unwrap();
+        out.push_str(&before);
+        writeln!(out, "    }}, || raw::{}({}), |call, result| {{", function.name, names).unwrap();
+        match written {
+            Written::Always => out.push_str(&after),
+            Written::If(condition) if !after.is_empty() => {
+                writeln!(out, "        if {} {{", condition).unwrap();
+                out.push_str(&after);
+                out.push_str("        }\n");
+            }
+            Written::If(_) | Written::Unknown => {}
+        }
+        writeln!(out, "        call.result = {};", result).unwrap();
+        if recorded {
+            // Failures and qualified successes go to the error history, whether or not a sink is set.
+            let format = vec!["{:?}"; function.arguments.len()].join(", ");
+            out.push_str("    });\n");
+            writeln!(out, "    ::error::record_traced_call(\"{}\", || format!(\"{}\", {}), result);", function.name, format, names).unwrap();
+            out.push_str("    result\n}\n");
+        } else {
+            out.push_str("    })\n}\n");
+        }
+    }
+    out
+}
+
+fn parameters(function: &Function) -> String {
+    function.arguments.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect::<Vec<_>>().join(", ")
+}
+
+fn ret(function: &Function) -> String {
+    function.ret.as_ref().map(|ret| format!(" -> {}", ret)).unwrap_or_default()
+}
+
+#[cfg(test)]
+#[test]
+fn test_argument_kinds() {
+    let mut functions = parse(include_str!("../src/lib.rs"));
+    functions.extend(parse(include_str!("../src/audio.rs")));
+    let kinds = |name: &str| {
+        let function = functions.iter().find(|f| f.name == name).unwrap();
+        function.arguments.iter().map(|(argument, ty)| format!("{} {:?}", argument, kind(function, argument, ty))).collect::<Vec<_>>()
+    };
+    assert_eq!(kinds("ovr_CalcEyePoses"), ["headPose Value", "hmdToEyeOffset Input", "outEyePoses Output"]);
+    assert_eq!(kinds("ovr_GetEyePoses"), [
+        "session Value", "frameIndex Value", "latencyMarker Value", "hmdToEyeOffset Input", "outEyePoses Output", "outSensorSampleTime Output",
+    ]);
+    assert_eq!(kinds("ovr_ReleaseHapticsClip"), ["hapticsClip Input"]);
+    assert_eq!(kinds("ovr_GetString"), ["session Value", "propertyName CString", "defaultVal CString"]);
+
+    let written = |name: &str| outputs_written(functions.iter().find(|f| f.name == name).unwrap());
+    assert_eq!(written("ovr_GetEyePoses"), Written::Always);
+    assert_eq!(written("ovr_GetSessionStatus"), Written::If("::OVR_SUCCESS(*result)"));
+    assert_eq!(written("ovr_GetFloatArray"), Written::If("*result > 0"));
+    let rendered = render(&functions, true);
+    assert!(rendered.contains("        if *result > 0 {\n            call.output(\"values\", ::trace::pointee(values));\n        }\n"));
+    assert!(rendered.contains("    ::error::record_traced_call(\"ovr_GetSessionStatus\", || format!(\"{:?}, {:?}\", session, sessionStatus), result);\n"));
+}
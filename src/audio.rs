@@ -2,15 +2,37 @@ use ::winapi::minwindef::UINT;
 use ::winapi::winnt::WCHAR;
 use ::winapi::guiddef::GUID;
 
+use ::std::fmt;
+use ::std::mem;
+use ::std::slice;
+
 use ::libc::{
     c_int,
     c_void
 };
 
-use ::ovrResult;
+use ::{
+    ovrError_AudioDeviceNotFound,
+    ovrHapticsBuffer,
+    ovrHapticsBufferSubmit_Enqueue,
+    ovrResult
+};
+use ::error::{
+    check_call,
+    Error,
+    Result
+};
+use ::haptics::{
+    decode_samples,
+    TOUCH_HAPTICS_SAMPLE_SIZE
+};
 
 pub const OVR_AUDIO_MAX_DEVICE_STR_SIZE: usize = 128;
 
+#[cfg(feature = "trace")]
+include!(concat!(env!("OUT_DIR"), "/trace_audio.rs"));
+
+#[cfg(not(feature = "trace"))]
 extern "C" {
     /// Gets the ID of the preferred VR audio output device.
     ///
@@ -33,12 +55,12 @@ extern "C" {
 
     /// Gets the GUID of the preferred VR audio device as a string.
     ///
-    /// **out** `deviceOutStrBuffer` A buffer where the GUID string for the device will copied to.
+    /// **out** `deviceOutStrBuffer` A buffer of `OVR_AUDIO_MAX_DEVICE_STR_SIZE` characters where the GUID string for the device will copied to.
     ///
     /// Returns an `ovrResult` indicating success or failure. In the case of failure, use
     ///         `ovr_GetLastErrorInfo` to get more information.
     ///
-    pub fn ovr_GetAudioDeviceOutGuidStr(deviceOutStrBuffer: [WCHAR; OVR_AUDIO_MAX_DEVICE_STR_SIZE]) -> ovrResult;
+    pub fn ovr_GetAudioDeviceOutGuidStr(deviceOutStrBuffer: *mut WCHAR) -> ovrResult;
 
 
     /// Gets the GUID of the preferred VR audio device.
@@ -53,12 +75,12 @@ extern "C" {
 
     /// Gets the GUID of the preferred VR microphone device as a string.
     ///
-    /// **out** `deviceInStrBuffer` A buffer where the GUID string for the device will copied to.
+    /// **out** `deviceInStrBuffer` A buffer of `OVR_AUDIO_MAX_DEVICE_STR_SIZE` characters where the GUID string for the device will copied to.
     ///
     /// Returns an `ovrResult` indicating success or failure. In the case of failure, use
     ///         `ovr_GetLastErrorInfo` to get more information.
     ///
-    pub fn ovr_GetAudioDeviceInGuidStr(deviceInStrBuffer: [WCHAR; OVR_AUDIO_MAX_DEVICE_STR_SIZE]) -> ovrResult;
+    pub fn ovr_GetAudioDeviceInGuidStr(deviceInStrBuffer: *mut WCHAR) -> ovrResult;
 
 
     /// Gets the GUID of the preferred VR microphone device.
@@ -103,6 +125,7 @@ pub struct ovrHapticsClip {
     /// Number of samples
     pub SamplesCount: c_int
 }
+#[cfg(not(feature = "trace"))]
 extern "C" {
 /// Reads an audio channel from Wav (Waveform Audio File) data.
 /// Input must be a byte buffer representing a valid Wav file. Audio samples from the specified channel are read,
@@ -141,4 +164,314 @@ pub fn ovr_ReleaseAudioChannelData(audioChannel: *mut ovrAudioChannelData);
 /// **in** `hapticsClip` pointer to a haptics clip
 ///
 pub fn ovr_ReleaseHapticsClip(hapticsClip: *mut ovrHapticsClip);
-}
\ No newline at end of file
+}
+
+/// Owns audio samples allocated by LibOVR, releasing them with `ovr_ReleaseAudioChannelData` on drop.
+///
+/// see [`ovr_ReadWavFromBuffer`](fn.ovr_ReadWavFromBuffer.html)
+#[derive(Debug)]
+pub struct AudioChannelData {
+    raw: ovrAudioChannelData,
+}
+
+impl AudioChannelData {
+    /// Reads a channel from Wav data using `ovr_ReadWavFromBuffer`.
+    ///
+    /// `channel` is the audio channel index to extract (0 for mono).
+    pub fn read_wav(data: &[u8], channel: usize) -> Result<AudioChannelData> {
+        let mut raw: ovrAudioChannelData = unsafe { mem::zeroed() };
+        check_call("ovr_ReadWavFromBuffer", || format!("{} bytes, channel {:?}", data.len(), channel), unsafe { ovr_ReadWavFromBuffer(&mut raw as *mut _, data.as_ptr() as *const c_void, data.len() as c_int, channel as c_int) })?;
+        Ok(AudioChannelData {
+            raw,
+        })
+    }
+
+    /// Takes ownership of audio data returned by LibOVR.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been filled in by `ovr_ReadWavFromBuffer` and not released yet.
+    pub unsafe fn from_raw(raw: ovrAudioChannelData) -> AudioChannelData {
+        AudioChannelData {
+            raw,
+        }
+    }
+
+    /// The samples, as floats in `[-1.0, 1.0]`.
+    pub fn samples(&self) -> &[f32] {
+        if self.raw.Samples.is_null() || self.raw.SamplesCount <= 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.raw.Samples, self.raw.SamplesCount as usize) }
+    }
+
+    /// The sample rate in Hz.
+    pub fn frequency(&self) -> u32 {
+        self.raw.Frequency as u32
+    }
+
+    /// The underlying `ovrAudioChannelData`, which remains owned by `self`.
+    pub fn as_raw(&self) -> &ovrAudioChannelData {
+        &self.raw
+    }
+
+    /// Generates Touch haptics from the audio using `ovr_GenHapticsFromAudioData`.
+    pub fn gen_haptics(&self, mode: ovrHapticsGenMode) -> Result<HapticsClip> {
+        HapticsClip::from_audio(&self.raw, mode)
+    }
+}
+
+impl Drop for AudioChannelData {
+    fn drop(&mut self) {
+        unsafe { ovr_ReleaseAudioChannelData(&mut self.raw as *mut _) }
+    }
+}
+
+/// Owns a haptics clip allocated by LibOVR, releasing it with `ovr_ReleaseHapticsClip` on drop.
+///
+/// The clip holds Touch haptics samples of `TOUCH_HAPTICS_SAMPLE_SIZE` bytes each.
+///
+/// see [`ovr_GenHapticsFromAudioData`](fn.ovr_GenHapticsFromAudioData.html)
+#[derive(Debug)]
+pub struct HapticsClip {
+    raw: ovrHapticsClip,
+}
+
+impl HapticsClip {
+    /// Generates a clip from audio using `ovr_GenHapticsFromAudioData`.
+    ///
+    /// `audio` may come from an [`AudioChannelData`](struct.AudioChannelData.html), or from
+    /// [`AudioChannel::as_raw`](../wav/struct.AudioChannel.html#method.as_raw) for audio decoded in Rust.
+    pub fn from_audio(audio: &ovrAudioChannelData, mode: ovrHapticsGenMode) -> Result<HapticsClip> {
+        let mut raw: ovrHapticsClip = unsafe { mem::zeroed() };
+        check_call("ovr_GenHapticsFromAudioData", || format!("mode {}", mode), unsafe { ovr_GenHapticsFromAudioData(&mut raw as *mut _, audio as *const _, mode) })?;
+        Ok(HapticsClip {
+            raw,
+        })
+    }
+
+    /// Takes ownership of a clip returned by LibOVR.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been filled in by `ovr_GenHapticsFromAudioData` and not released yet.
+    pub unsafe fn from_raw(raw: ovrHapticsClip) -> HapticsClip {
+        HapticsClip {
+            raw,
+        }
+    }
+
+    /// The number of samples in the clip.
+    pub fn len(&self) -> usize {
+        if self.raw.Samples.is_null() { 0 } else { self.raw.SamplesCount.max(0) as usize }
+    }
+
+    /// Returns true if the clip has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The samples in their opaque byte format.
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.is_empty() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.raw.Samples as *const u8, self.len() * TOUCH_HAPTICS_SAMPLE_SIZE) }
+    }
+
+    /// The samples as amplitudes in `[0.0, 1.0]`, as accepted by
+    /// [`HapticsPlayer::play`](../haptics/struct.HapticsPlayer.html#method.play).
+    pub fn to_amplitudes(&self) -> Vec<f32> {
+        decode_samples(self.as_bytes(), TOUCH_HAPTICS_SAMPLE_SIZE)
+    }
+
+    /// Describes the whole clip as an `ovrHapticsBuffer` for `ovr_SubmitControllerVibration`.
+    ///
+    /// The buffer borrows the clip's samples, so the clip must outlive the submission call. Clips longer
+    /// than `ovrTouchHapticsDesc::SubmitMaxSamples` must be submitted in parts, for example by playing
+    /// the amplitudes through a [`HapticsPlayer`](../haptics/struct.HapticsPlayer.html).
+    pub fn as_buffer(&self) -> ovrHapticsBuffer {
+        ovrHapticsBuffer {
+            Samples: self.raw.Samples,
+            SamplesCount: self.len() as c_int,
+            SubmitMode: ovrHapticsBufferSubmit_Enqueue,
+        }
+    }
+
+    /// The underlying `ovrHapticsClip`, which remains owned by `self`.
+    pub fn as_raw(&self) -> &ovrHapticsClip {
+        &self.raw
+    }
+}
+
+impl Drop for HapticsClip {
+    fn drop(&mut self) {
+        unsafe { ovr_ReleaseHapticsClip(&mut self.raw as *mut _) }
+    }
+}
+
+/// Formats a GUID in the usual registry form, e.g. `{0F4C9E2A-55B1-4E8D-9C7A-1B2C3D4E5F60}`.
+pub fn format_guid(guid: &GUID) -> String {
+    format!("{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        guid.Data1, guid.Data2, guid.Data3,
+        guid.Data4[0], guid.Data4[1], guid.Data4[2], guid.Data4[3],
+        guid.Data4[4], guid.Data4[5], guid.Data4[6], guid.Data4[7])
+}
+
+/// Returns the last `{...}` group of a device ID string, which holds the device GUID in endpoint IDs
+/// such as `{0.0.0.00000000}.{0f4c9e2a-55b1-4e8d-9c7a-1b2c3d4e5f60}`.
+fn guid_part(id: &str) -> Option<&str> {
+    let start = id.rfind('{')?;
+    let end = start + id[start..].find('}')?;
+    Some(&id[start..end + 1])
+}
+
+/// One of the audio devices built into the Rift, as selected by the user in the Oculus app.
+#[derive(Clone)]
+pub struct RiftAudioDevice {
+    id: String,
+    guid: GUID,
+    wave_id: UINT,
+}
+
+impl RiftAudioDevice {
+    fn query(
+        names: [&'static str; 3],
+        get_str: fn(*mut WCHAR) -> ovrResult,
+        get_guid: fn(*mut GUID) -> ovrResult,
+        get_wave_id: fn(*mut UINT) -> ovrResult,
+    ) -> Result<Option<RiftAudioDevice>> {
+        let mut buffer = [0 as WCHAR; OVR_AUDIO_MAX_DEVICE_STR_SIZE];
+        let mut guid: GUID = unsafe { mem::zeroed() };
+        let mut wave_id: UINT = 0;
+        // Each call is checked before the next, so that the error message read by `check_call` is its own.
+        let found = |name, result| match check_call(name, String::new, result) {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.result() == ovrError_AudioDeviceNotFound => Ok(false),
+            Err(e) => Err(e),
+        };
+        if !found(names[0], get_str(buffer.as_mut_ptr()))?
+            || !found(names[1], get_guid(&mut guid as *mut _))?
+            || !found(names[2], get_wave_id(&mut wave_id as *mut _))? {
+            return Ok(None);
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Ok(Some(RiftAudioDevice {
+            id: String::from_utf16_lossy(&buffer[..len]),
+            guid,
+            wave_id,
+        }))
+    }
+
+    /// The device ID string, as returned by `ovr_GetAudioDeviceOutGuidStr` or `ovr_GetAudioDeviceInGuidStr`.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The device GUID, as returned by `ovr_GetAudioDeviceOutGuid` or `ovr_GetAudioDeviceInGuid`.
+    pub fn guid(&self) -> GUID {
+        self.guid
+    }
+
+    /// The waveOut/waveIn device ID, as returned by `ovr_GetAudioDeviceOutWaveId` or `ovr_GetAudioDeviceInWaveId`.
+    pub fn wave_id(&self) -> UINT {
+        self.wave_id
+    }
+
+    /// Returns true if `device_id` identifies this device.
+    ///
+    /// Device IDs are compared by the GUID they contain, ignoring case, so both bare GUIDs and full
+    /// Windows audio endpoint IDs, such as those listed by WASAPI-based libraries like cpal, are accepted.
+    pub fn matches(&self, device_id: &str) -> bool {
+        let own = guid_part(&self.id).map(str::to_owned).unwrap_or_else(|| format_guid(&self.guid));
+        match guid_part(device_id) {
+            Some(other) => other.eq_ignore_ascii_case(&own),
+            None => false,
+        }
+    }
+
+    /// Finds this device in a list of device IDs, returning its index.
+    ///
+    /// ```no_run
+    /// # use ovr_sys::audio::RiftAudioDevices;
+    /// # let ids: Vec<String> = vec![];
+    /// let devices = RiftAudioDevices::query().unwrap();
+    /// if let Some(mic) = devices.input {
+    ///     let index = mic.position_in(&ids);
+    /// }
+    /// ```
+    pub fn position_in<I, S>(&self, device_ids: I) -> Option<usize>
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        device_ids.into_iter().position(|id| self.matches(id.as_ref()))
+    }
+}
+
+impl fmt::Debug for RiftAudioDevice {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("RiftAudioDevice")
+            .field("id", &self.id)
+            .field("guid", &format_guid(&self.guid))
+            .field("wave_id", &self.wave_id)
+            .finish()
+    }
+}
+
+/// The Rift headphones and microphone, for routing application audio to the headset.
+///
+/// Requires LibOVR to be initialized with `ovr_Initialize`.
+#[derive(Debug, Clone)]
+pub struct RiftAudioDevices {
+    /// The preferred VR audio output device, if one is available.
+    pub output: Option<RiftAudioDevice>,
+    /// The preferred VR microphone, if one is available.
+    pub input: Option<RiftAudioDevice>,
+}
+
+impl RiftAudioDevices {
+    /// Queries the preferred VR audio devices.
+    ///
+    /// A device that the runtime reports as `ovrError_AudioDeviceNotFound` is returned as `None`.
+    /// Other failures are returned as errors.
+    pub fn query() -> Result<RiftAudioDevices> {
+        Ok(RiftAudioDevices {
+            output: RiftAudioDevice::query(
+                ["ovr_GetAudioDeviceOutGuidStr", "ovr_GetAudioDeviceOutGuid", "ovr_GetAudioDeviceOutWaveId"],
+                |id| unsafe { ovr_GetAudioDeviceOutGuidStr(id) },
+                |guid| unsafe { ovr_GetAudioDeviceOutGuid(guid) },
+                |wave_id| unsafe { ovr_GetAudioDeviceOutWaveId(wave_id) },
+            )?,
+            input: RiftAudioDevice::query(
+                ["ovr_GetAudioDeviceInGuidStr", "ovr_GetAudioDeviceInGuid", "ovr_GetAudioDeviceInWaveId"],
+                |id| unsafe { ovr_GetAudioDeviceInGuidStr(id) },
+                |guid| unsafe { ovr_GetAudioDeviceInGuid(guid) },
+                |wave_id| unsafe { ovr_GetAudioDeviceInWaveId(wave_id) },
+            )?,
+        })
+    }
+
+    /// The preferred output device, or an `ovrError_AudioDeviceNotFound` error if there is none.
+    pub fn require_output(&self) -> Result<&RiftAudioDevice> {
+        self.output.as_ref().ok_or_else(|| Error::new(ovrError_AudioDeviceNotFound, "no VR audio output device"))
+    }
+
+    /// The preferred microphone, or an `ovrError_AudioDeviceNotFound` error if there is none.
+    pub fn require_input(&self) -> Result<&RiftAudioDevice> {
+        self.input.as_ref().ok_or_else(|| Error::new(ovrError_AudioDeviceNotFound, "no VR audio input device"))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_audio_device_matches() {
+    let device = RiftAudioDevice {
+        id: "{0.0.1.00000000}.{0f4c9e2a-55b1-4e8d-9c7a-1b2c3d4e5f60}".to_owned(),
+        guid: unsafe { mem::zeroed() },
+        wave_id: 0,
+    };
+    assert!(device.matches("{0.0.1.00000000}.{0F4C9E2A-55B1-4E8D-9C7A-1B2C3D4E5F60}"));
+    assert!(device.matches("{0f4c9e2a-55b1-4e8d-9c7a-1b2c3d4e5f60}"));
+    assert!(!device.matches("{0.0.1.00000000}.{00000000-0000-0000-0000-000000000000}"));
+    assert!(!device.matches("Headphones (Rift Audio)"));
+    assert_eq!(device.position_in(&["Speakers", "{0F4C9E2A-55B1-4E8D-9C7A-1B2C3D4E5F60}"]), Some(1));
+}
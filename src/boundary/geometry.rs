@@ -0,0 +1,451 @@
+use ::std::f32::consts::FRAC_PI_2;
+
+use ::{
+    ovrVector2f,
+    ovrVector3f
+};
+use ::math::{
+    add2,
+    closest_on_segment,
+    cross2,
+    floor,
+    length2,
+    rotate2,
+    scale2,
+    sub2,
+    vec2,
+    vec3
+};
+
+/// Number of grid cells along the longer side of the polygon used by the rectangle search.
+const RECT_GRID_CELLS: usize = 128;
+/// Number of grid cells along the longer side of the polygon used by the spawn point search.
+const SPAWN_GRID_CELLS: usize = 64;
+/// Number of evenly spaced orientations tried by the oriented rectangle search, in addition to the
+/// directions of the longest edges.
+const RECT_ANGLE_STEPS: usize = 18;
+/// Number of longest edges whose directions are tried by the oriented rectangle search.
+const RECT_EDGE_ANGLES: usize = 8;
+
+/// Result of testing a point against a boundary polygon, the offline counterpart of `ovrBoundaryTestResult`.
+#[derive(Debug, Copy, Clone)]
+pub struct BoundaryTest {
+    /// Whether the point lies inside the polygon, ignoring height.
+    pub inside: bool,
+    /// Horizontal distance to the closest point on the boundary walls.
+    pub distance: f32,
+    /// Closest point on the boundary walls, at the height of the tested point.
+    pub closest_point: ovrVector3f,
+    /// Horizontal unit normal of the wall at the closest point, facing into the play space.
+    pub closest_point_normal: ovrVector3f,
+}
+
+/// A rectangle on the floor, rotated about the vertical axis.
+#[derive(Debug, Copy, Clone)]
+pub struct BoundaryRect {
+    /// Center of the rectangle, at floor height.
+    pub center: ovrVector3f,
+    /// Extent along the local X axis.
+    pub width: f32,
+    /// Extent along the local Z axis.
+    pub length: f32,
+    /// Rotation about the Y axis in radians, following the right-handed convention of LibOVR.
+    pub yaw: f32,
+}
+
+impl BoundaryRect {
+    /// Floor area of the rectangle in square meters.
+    pub fn area(&self) -> f32 {
+        self.width * self.length
+    }
+
+    /// Corners of the rectangle in order around its edge.
+    pub fn corners(&self) -> [ovrVector3f; 4] {
+        let center = floor(self.center);
+        let (w, l) = (self.width / 2.0, self.length / 2.0);
+        let corner = |x: f32, z: f32| {
+            let p = add2(center, rotate2(vec2(x, z), -self.yaw));
+            vec3(p.x, self.center.y, p.y)
+        };
+        [corner(-w, -l), corner(w, -l), corner(w, l), corner(-w, l)]
+    }
+
+    /// Whether a point lies inside the rectangle, ignoring height.
+    pub fn contains(&self, point: ovrVector3f) -> bool {
+        let local = rotate2(sub2(floor(point), floor(self.center)), self.yaw);
+        local.x.abs() <= self.width / 2.0 && local.y.abs() <= self.length / 2.0
+    }
+}
+
+/// A boundary polygon on the floor, as returned by [`Boundary::polygon`](struct.Boundary.html#method.polygon).
+///
+/// All queries work in the horizontal plane, treating the boundary as vertical walls standing on
+/// the polygon, so they are usable offline on recorded boundary data.
+///
+/// ```
+/// # use ovr_sys::boundary::BoundaryPolygon;
+/// # use ovr_sys::ovrVector3f;
+/// let point = |x, z| ovrVector3f { _align: [], x, y: 0.0, z };
+/// let room = BoundaryPolygon::new(vec![point(0.0, 0.0), point(0.0, 3.0), point(4.0, 3.0), point(4.0, 0.0)]);
+/// assert_eq!(room.area(), 12.0);
+/// assert!(room.contains(point(1.0, 1.0)));
+/// assert_eq!(room.distance(point(1.0, 1.0)), 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundaryPolygon {
+    points: Vec<ovrVector3f>,
+    flat: Vec<ovrVector2f>,
+    floor_height: f32,
+    signed_area: f32,
+}
+
+impl BoundaryPolygon {
+    /// Creates a polygon from its floor points, in either winding order.
+    pub fn new(points: Vec<ovrVector3f>) -> BoundaryPolygon {
+        let flat: Vec<ovrVector2f> = points.iter().map(|&p| floor(p)).collect();
+        let floor_height = if points.is_empty() {
+            0.0
+        } else {
+            points.iter().map(|p| p.y).sum::<f32>() / points.len() as f32
+        };
+        let signed_area = edges(&flat).map(|(a, b)| cross2(a, b)).sum::<f32>() / 2.0;
+        BoundaryPolygon {
+            points,
+            flat,
+            floor_height,
+            signed_area,
+        }
+    }
+
+    /// The floor points of the polygon.
+    pub fn points(&self) -> &[ovrVector3f] {
+        &self.points
+    }
+
+    /// Average height of the floor points.
+    pub fn floor_height(&self) -> f32 {
+        self.floor_height
+    }
+
+    /// Floor area of the polygon in square meters.
+    pub fn area(&self) -> f32 {
+        self.signed_area.abs()
+    }
+
+    /// Whether a point lies inside the polygon, ignoring height.
+    pub fn contains(&self, point: ovrVector3f) -> bool {
+        inside(&self.flat, floor(point))
+    }
+
+    /// Closest point on the boundary walls, at the height of `point`.
+    pub fn closest_point(&self, point: ovrVector3f) -> ovrVector3f {
+        self.test_point(point).closest_point
+    }
+
+    /// Horizontal distance from a point to the boundary walls.
+    pub fn distance(&self, point: ovrVector3f) -> f32 {
+        self.test_point(point).distance
+    }
+
+    /// Horizontal unit normal of the wall closest to a point, facing into the play space.
+    pub fn normal(&self, point: ovrVector3f) -> ovrVector3f {
+        self.test_point(point).closest_point_normal
+    }
+
+    /// Tests a point against the polygon, mirroring `ovr_TestBoundaryPoint`.
+    ///
+    /// A polygon with no points reports an infinite distance.
+    pub fn test_point(&self, point: ovrVector3f) -> BoundaryTest {
+        let p = floor(point);
+        let mut best = (f32::INFINITY, p, vec2(0.0, 0.0));
+        for (a, b) in edges(&self.flat) {
+            let closest = closest_on_segment(p, a, b);
+            let distance = length2(sub2(p, closest));
+            if distance < best.0 {
+                best = (distance, closest, sub2(b, a));
+            }
+        }
+        let (distance, closest, edge) = best;
+        let length = length2(edge);
+        let normal = if length == 0.0 {
+            vec2(0.0, 0.0)
+        } else if self.signed_area >= 0.0 {
+            vec2(-edge.y / length, edge.x / length)
+        } else {
+            vec2(edge.y / length, -edge.x / length)
+        };
+        BoundaryTest {
+            inside: inside(&self.flat, p),
+            distance,
+            closest_point: vec3(closest.x, point.y, closest.y),
+            closest_point_normal: vec3(normal.x, 0.0, normal.y),
+        }
+    }
+
+    /// Finds the largest axis aligned rectangle that fits inside the polygon.
+    ///
+    /// The search runs on a grid with 128 cells along the longer side of the polygon, so the result
+    /// always lies inside the polygon and may be up to one cell smaller than the true optimum.
+    /// Returns `None` for polygons that are degenerate or too thin to hold a single cell.
+    pub fn largest_axis_aligned_rect(&self) -> Option<BoundaryRect> {
+        self.rect_at_angle(0.0)
+    }
+
+    /// Finds the largest rectangle of any orientation that fits inside the polygon.
+    ///
+    /// Orientations are tried in 5 degree steps and along the longest edges of the polygon, with
+    /// the same grid search as [`largest_axis_aligned_rect`](#method.largest_axis_aligned_rect).
+    pub fn largest_rect(&self) -> Option<BoundaryRect> {
+        let mut angles: Vec<f32> = (0..RECT_ANGLE_STEPS).map(|i| i as f32 * FRAC_PI_2 / RECT_ANGLE_STEPS as f32).collect();
+        let mut lengths: Vec<(f32, ovrVector2f)> = edges(&self.flat).map(|(a, b)| (length2(sub2(b, a)), sub2(b, a))).collect();
+        lengths.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(::std::cmp::Ordering::Equal));
+        for &(length, edge) in lengths.iter().take(RECT_EDGE_ANGLES) {
+            if length > 0.0 {
+                let angle = edge.y.atan2(edge.x).rem_euclid(FRAC_PI_2);
+                if angles.iter().all(|&a| (a - angle).abs() > 1e-3) {
+                    angles.push(angle);
+                }
+            }
+        }
+        angles.into_iter()
+            .filter_map(|angle| self.rect_at_angle(angle))
+            .fold(None, |best: Option<BoundaryRect>, rect| match best {
+                Some(best) if best.area() >= rect.area() => Some(best),
+                _ => Some(rect),
+            })
+    }
+
+    /// Finds the point inside the polygon that is furthest from the walls, if it has at least
+    /// `min_clearance` meters of free space around it.
+    ///
+    /// The point is returned at floor height. It is found by a grid search refined locally, and
+    /// lies within a few millimeters of the true optimum.
+    pub fn spawn_point(&self, min_clearance: f32) -> Option<ovrVector3f> {
+        let (min, max) = bounds(&self.flat)?;
+        let size = (max.x - min.x).max(max.y - min.y);
+        if size <= 0.0 {
+            return None;
+        }
+        let clearance = |p: ovrVector2f| {
+            if inside(&self.flat, p) {
+                edges(&self.flat).map(|(a, b)| length2(sub2(p, closest_on_segment(p, a, b)))).fold(f32::INFINITY, f32::min)
+            } else {
+                -1.0
+            }
+        };
+        let step = size / SPAWN_GRID_CELLS as f32;
+        let mut best = (-1.0, min);
+        for j in 0..(((max.y - min.y) / step).ceil() as usize).max(1) {
+            for i in 0..(((max.x - min.x) / step).ceil() as usize).max(1) {
+                let p = vec2(min.x + (i as f32 + 0.5) * step, min.y + (j as f32 + 0.5) * step);
+                let c = clearance(p);
+                if c > best.0 {
+                    best = (c, p);
+                }
+            }
+        }
+        if best.0 < 0.0 {
+            return None;
+        }
+        let mut step = step / 2.0;
+        while step > size * 1e-4 {
+            let (c, p) = best;
+            let moved = [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0), (1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)].iter()
+                .map(|&(x, y)| add2(p, vec2(x * step, y * step)))
+                .map(|q| (clearance(q), q))
+                .fold(best, |best, candidate| if candidate.0 > best.0 { candidate } else { best });
+            if moved.0 > c {
+                best = moved;
+            } else {
+                step /= 2.0;
+            }
+        }
+        if best.0 >= min_clearance {
+            Some(vec3(best.1.x, self.floor_height, best.1.y))
+        } else {
+            None
+        }
+    }
+
+    /// Finds the largest rectangle whose sides run at `angle` radians counter-clockwise from the X
+    /// axis in the floor plane.
+    fn rect_at_angle(&self, angle: f32) -> Option<BoundaryRect> {
+        let rotated: Vec<ovrVector2f> = self.flat.iter().map(|&p| rotate2(p, -angle)).collect();
+        let (min, max) = largest_grid_rect(&rotated, RECT_GRID_CELLS)?;
+        let center = rotate2(scale2(add2(min, max), 0.5), angle);
+        Some(BoundaryRect {
+            center: vec3(center.x, self.floor_height, center.y),
+            width: max.x - min.x,
+            length: max.y - min.y,
+            yaw: -angle,
+        })
+    }
+}
+
+/// Iterates over the edges of a closed polygon.
+fn edges<'a>(points: &'a [ovrVector2f]) -> impl Iterator<Item = (ovrVector2f, ovrVector2f)> + 'a {
+    points.iter().enumerate().map(move |(i, &a)| (a, points[(i + 1) % points.len()]))
+}
+
+/// Even-odd point in polygon test.
+fn inside(points: &[ovrVector2f], p: ovrVector2f) -> bool {
+    edges(points)
+        .filter(|&(a, b)| (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y))
+        .count() % 2 == 1
+}
+
+fn bounds(points: &[ovrVector2f]) -> Option<(ovrVector2f, ovrVector2f)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), p| {
+        (vec2(min.x.min(p.x), min.y.min(p.y)), vec2(max.x.max(p.x), max.y.max(p.y)))
+    }))
+}
+
+/// Whether the segment from `a` to `b` passes through the open box from `min` to `max`.
+fn crosses_box(a: ovrVector2f, b: ovrVector2f, min: ovrVector2f, max: ovrVector2f) -> bool {
+    let d = sub2(b, a);
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for &(p, q) in &[(-d.x, a.x - min.x), (d.x, max.x - a.x), (-d.y, a.y - min.y), (d.y, max.y - a.y)] {
+        if p == 0.0 {
+            if q <= 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    t0 < t1
+}
+
+/// Finds the largest axis aligned rectangle made of whole grid cells inside the polygon, where the
+/// grid has `cells` cells along the longer side of the bounding box.
+fn largest_grid_rect(points: &[ovrVector2f], cells: usize) -> Option<(ovrVector2f, ovrVector2f)> {
+    if points.len() < 3 {
+        return None;
+    }
+    let (min, max) = bounds(points)?;
+    let size = (max.x - min.x).max(max.y - min.y);
+    if size <= 0.0 {
+        return None;
+    }
+    let step = size / cells as f32;
+    let nx = (((max.x - min.x) / step).ceil() as usize).max(1);
+    let nz = (((max.y - min.y) / step).ceil() as usize).max(1);
+    let cell_min = |i: usize, j: usize| vec2(min.x + i as f32 * step, min.y + j as f32 * step);
+
+    // A cell is usable if its center is inside and no edge passes through it, which together mean
+    // the whole cell is inside.
+    let mut free = vec![false; nx * nz];
+    for j in 0..nz {
+        for i in 0..nx {
+            free[j * nx + i] = inside(points, add2(cell_min(i, j), vec2(step / 2.0, step / 2.0)));
+        }
+    }
+    let margin = step * 1e-4;
+    for (a, b) in edges(points) {
+        let cell = |v: f32, origin: f32, n: usize| (((v - origin) / step).floor().max(0.0) as usize).min(n - 1);
+        for j in cell(a.y.min(b.y), min.y, nz)..=cell(a.y.max(b.y), min.y, nz) {
+            for i in cell(a.x.min(b.x), min.x, nx)..=cell(a.x.max(b.x), min.x, nx) {
+                let lo = cell_min(i, j);
+                let hi = cell_min(i + 1, j + 1);
+                if free[j * nx + i] && crosses_box(a, b, vec2(lo.x + margin, lo.y + margin), vec2(hi.x - margin, hi.y - margin)) {
+                    free[j * nx + i] = false;
+                }
+            }
+        }
+    }
+
+    // Largest rectangle of free cells, using a histogram of free run heights per row.
+    let mut heights = vec![0usize; nx];
+    let mut best = (0, 0, 0, 0, 0);
+    for j in 0..nz {
+        for i in 0..nx {
+            heights[i] = if free[j * nx + i] { heights[i] + 1 } else { 0 };
+        }
+        let mut stack: Vec<usize> = Vec::new();
+        for i in 0..=nx {
+            let height = if i < nx { heights[i] } else { 0 };
+            while let Some(&top) = stack.last() {
+                if heights[top] < height {
+                    break;
+                }
+                stack.pop();
+                let start = stack.last().map_or(0, |&s| s + 1);
+                let area = heights[top] * (i - start);
+                if area > best.0 {
+                    best = (area, start, i, j + 1 - heights[top], j + 1);
+                }
+            }
+            stack.push(i);
+        }
+    }
+    let (area, i0, i1, j0, j1) = best;
+    if area == 0 {
+        return None;
+    }
+    Some((cell_min(i0, j0), cell_min(i1, j1)))
+}
+
+#[cfg(test)]
+fn polygon(points: &[(f32, f32)]) -> BoundaryPolygon {
+    BoundaryPolygon::new(points.iter().map(|&(x, z)| vec3(x, 0.0, z)).collect())
+}
+
+#[cfg(test)]
+#[test]
+fn test_point_queries() {
+    // Both windings give the same results.
+    for room in &[polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]), polygon(&[(0.0, 0.0), (0.0, 3.0), (4.0, 3.0), (4.0, 0.0)])] {
+        assert_eq!(room.area(), 12.0);
+        let test = room.test_point(vec3(3.5, 1.7, 1.5));
+        assert!(test.inside);
+        assert!((test.distance - 0.5).abs() < 1e-6);
+        assert_eq!((test.closest_point.x, test.closest_point.y, test.closest_point.z), (4.0, 1.7, 1.5));
+        assert_eq!((test.closest_point_normal.x, test.closest_point_normal.z), (-1.0, 0.0));
+
+        let outside = room.test_point(vec3(-1.0, 0.0, 1.0));
+        assert!(!outside.inside);
+        assert_eq!(outside.distance, 1.0);
+        assert_eq!((outside.closest_point_normal.x, outside.closest_point_normal.z), (1.0, 0.0));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_inscribed_rects() {
+    // In an L shaped room two 4x2 strips tie for the largest rectangle, so check the area rather
+    // than the placement.
+    let room = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.0), (2.0, 4.0), (0.0, 4.0)]);
+    let rect = room.largest_axis_aligned_rect().unwrap();
+    assert!((rect.area() - 8.0).abs() < 1e-3);
+    for &corner in &rect.corners() {
+        assert!(room.distance(corner) < 1e-3 || room.contains(corner));
+    }
+
+    // A square rotated by 30 degrees only fits a small axis aligned rectangle, but the oriented
+    // search recovers the whole square.
+    let (sin, cos) = (30.0f32.to_radians().sin(), 30.0f32.to_radians().cos());
+    let diamond = polygon(&[(0.0, 0.0), (2.0 * cos, 2.0 * sin), (2.0 * cos - 2.0 * sin, 2.0 * sin + 2.0 * cos), (-2.0 * sin, 2.0 * cos)]);
+    assert!(diamond.largest_axis_aligned_rect().unwrap().area() < 3.0);
+    let rect = diamond.largest_rect().unwrap();
+    assert!(rect.area() > 3.9);
+    assert!(diamond.contains(rect.center));
+
+    assert!(polygon(&[(0.0, 0.0), (1.0, 0.0)]).largest_rect().is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_spawn_point() {
+    let room = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]);
+    let spawn = room.spawn_point(0.5).unwrap();
+    assert!((room.distance(spawn) - 1.0).abs() < 1e-2);
+    assert!((spawn.z - 1.0).abs() < 1e-2);
+    assert!(room.spawn_point(1.5).is_none());
+    assert!(polygon(&[]).spawn_point(0.0).is_none());
+}
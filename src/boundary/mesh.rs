@@ -0,0 +1,266 @@
+use ::{
+    ovrBoundaryLookAndFeel,
+    ovrColorf,
+    ovrVector2f,
+    ovrVector3f
+};
+use ::math::{
+    add2,
+    cross2,
+    floor,
+    length2,
+    scale2,
+    sub2,
+    vec2,
+    vec3
+};
+use super::BoundaryPolygon;
+
+/// Longest horizontal span of a single wall quad, so that per-vertex distances interpolate smoothly.
+const WALL_SEGMENT_LENGTH: f32 = 0.25;
+
+/// A vertex of a boundary mesh.
+#[derive(Debug, Copy, Clone)]
+pub struct BoundaryVertex {
+    /// Position in the tracking space the boundary was read in.
+    pub position: ovrVector3f,
+    /// Unit normal facing into the play space for walls, and up for floors.
+    pub normal: ovrVector3f,
+    /// Texture coordinates. Walls use meters along the wall for U and 0 at the floor to 1 at the
+    /// top for V. Floors use meters from the corner of their bounding box on X and Z.
+    pub uv: ovrVector2f,
+    /// Horizontal distance from the vertex to the reference boundary the mesh was built against,
+    /// for fading the mesh in by proximity.
+    pub distance: f32,
+}
+
+/// An indexed triangle mesh built from a boundary polygon, for drawing custom Guardian visuals.
+///
+/// Triangles wind counter-clockwise when seen from the front, that is from inside the play space
+/// for walls and from above for floors.
+///
+/// ```
+/// # use ovr_sys::boundary::{BoundaryMesh, BoundaryPolygon};
+/// # use ovr_sys::{ovrColorf, ovrVector3f};
+/// let point = |x, z| ovrVector3f { _align: [], x, y: 0.0, z };
+/// let outer = BoundaryPolygon::new(vec![point(-2.0, -2.0), point(-2.0, 2.0), point(2.0, 2.0), point(2.0, -2.0)]);
+/// let play_area = BoundaryPolygon::new(vec![point(-1.0, -1.0), point(-1.0, 1.0), point(1.0, 1.0), point(1.0, -1.0)]);
+/// let cyan = ovrColorf { _align: [], r: 0.0, g: 1.0, b: 1.0, a: 1.0 };
+///
+/// let walls = BoundaryMesh::walls(&outer, &play_area, 2.5, cyan);
+/// assert!(walls.vertices.iter().all(|v| v.distance >= 1.0));
+/// let floor = BoundaryMesh::floor(&play_area, &outer, cyan).unwrap();
+/// assert_eq!(floor.indices.len(), 6);
+/// // Use the same colour for the runtime's own boundary, see `Boundary::set_look_and_feel`.
+/// let look = floor.look_and_feel();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundaryMesh {
+    /// Vertices of the mesh.
+    pub vertices: Vec<BoundaryVertex>,
+    /// Triangle list indices into `vertices`.
+    pub indices: Vec<u32>,
+    /// Colour to draw the mesh with.
+    pub color: ovrColorf,
+}
+
+impl BoundaryMesh {
+    /// Builds vertical walls of `height` meters standing on a boundary, usually the outer boundary.
+    ///
+    /// Vertex distances are measured to `reference`, usually the play area.
+    pub fn walls(boundary: &BoundaryPolygon, reference: &BoundaryPolygon, height: f32, color: ovrColorf) -> BoundaryMesh {
+        let points = counter_clockwise(boundary);
+        let floor_height = boundary.floor_height();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut along = 0.0;
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let edge = sub2(b, a);
+            let length = length2(edge);
+            if length == 0.0 {
+                continue;
+            }
+            let normal = vec3(-edge.y / length, 0.0, edge.x / length);
+            let segments = (length / WALL_SEGMENT_LENGTH).ceil().max(1.0) as usize;
+            let first = vertices.len() as u32;
+            for s in 0..=segments {
+                let t = s as f32 / segments as f32;
+                let p = add2(a, scale2(edge, t));
+                let u = along + length * t;
+                let distance = reference.distance(vec3(p.x, floor_height, p.y));
+                for &(y, v) in &[(floor_height, 0.0), (floor_height + height, 1.0)] {
+                    vertices.push(BoundaryVertex {
+                        position: vec3(p.x, y, p.y),
+                        normal,
+                        uv: vec2(u, v),
+                        distance,
+                    });
+                }
+            }
+            for s in 0..segments as u32 {
+                let (b0, t0, b1, t1) = (first + 2 * s, first + 2 * s + 1, first + 2 * s + 2, first + 2 * s + 3);
+                indices.extend_from_slice(&[b0, b1, t1, b0, t1, t0]);
+            }
+            along += length;
+        }
+        BoundaryMesh {
+            vertices,
+            indices,
+            color,
+        }
+    }
+
+    /// Builds a flat floor covering a boundary, usually the play area.
+    ///
+    /// Vertex distances are measured to `reference`, usually the outer boundary. Returns `None` if
+    /// the polygon cannot be triangulated, because it has fewer than three distinct points or
+    /// intersects itself.
+    pub fn floor(boundary: &BoundaryPolygon, reference: &BoundaryPolygon, color: ovrColorf) -> Option<BoundaryMesh> {
+        let points = counter_clockwise(boundary);
+        let triangles = triangulate(&points)?;
+        let floor_height = boundary.floor_height();
+        let min = points.iter().fold(vec2(f32::INFINITY, f32::INFINITY), |min, p| vec2(min.x.min(p.x), min.y.min(p.y)));
+        let vertices = points.iter().map(|&p| {
+            let position = vec3(p.x, floor_height, p.y);
+            BoundaryVertex {
+                position,
+                normal: vec3(0.0, 1.0, 0.0),
+                uv: sub2(p, min),
+                distance: reference.distance(position),
+            }
+        }).collect();
+        // Counter-clockwise in the X-Z plane is clockwise when seen from above, since Z points
+        // towards the viewer in LibOVR's right-handed coordinates.
+        let indices = triangles.iter().flat_map(|&[a, b, c]| vec![a, c, b]).collect();
+        Some(BoundaryMesh {
+            vertices,
+            indices,
+            color,
+        })
+    }
+
+    /// The runtime look and feel matching this mesh, for `Boundary::set_look_and_feel`.
+    ///
+    /// The runtime ignores the alpha channel.
+    pub fn look_and_feel(&self) -> ovrBoundaryLookAndFeel {
+        ovrBoundaryLookAndFeel {
+            Color: self.color,
+        }
+    }
+}
+
+/// The floor points of a polygon, ordered counter-clockwise in the X-Z plane.
+fn counter_clockwise(polygon: &BoundaryPolygon) -> Vec<ovrVector2f> {
+    let mut points: Vec<ovrVector2f> = polygon.points().iter().map(|&p| floor(p)).collect();
+    if twice_area(&points) < 0.0 {
+        points.reverse();
+    }
+    points
+}
+
+/// Twice the signed area of a polygon, positive when counter-clockwise in the X-Z plane.
+fn twice_area(points: &[ovrVector2f]) -> f32 {
+    (0..points.len()).map(|i| cross2(points[i], points[(i + 1) % points.len()])).sum()
+}
+
+/// Triangulates a counter-clockwise simple polygon by ear clipping.
+fn triangulate(points: &[ovrVector2f]) -> Option<Vec<[u32; 3]>> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+        let turn = |(a, b, c): (usize, usize, usize)| cross2(sub2(points[b], points[a]), sub2(points[c], points[b]));
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = corner(i);
+            turn((a, b, c)) > 0.0 && remaining.iter()
+                .filter(|&&j| j != a && j != b && j != c)
+                .all(|&j| !in_triangle(points[j], points[a], points[b], points[c]))
+        });
+        match ear {
+            Some(i) => {
+                let (a, b, c) = corner(i);
+                triangles.push([a as u32, b as u32, c as u32]);
+                remaining.remove(i);
+            }
+            // Collinear and repeated points are not ears, but can be dropped without losing area.
+            None => {
+                let flat = (0..n).find(|&i| turn(corner(i)).abs() <= 1e-9)?;
+                remaining.remove(flat);
+            }
+        }
+    }
+    if remaining.len() == 3 {
+        let (a, b, c) = (remaining[0], remaining[1], remaining[2]);
+        if cross2(sub2(points[b], points[a]), sub2(points[c], points[b])) > 0.0 {
+            triangles.push([a as u32, b as u32, c as u32]);
+        }
+    }
+    // Ear clipping always finishes on self-intersecting polygons too, but the triangles then cover
+    // a different area from the polygon.
+    let covered: f32 = triangles.iter().map(|&[a, b, c]| cross2(sub2(points[b as usize], points[a as usize]), sub2(points[c as usize], points[a as usize]))).sum();
+    let expected = twice_area(points);
+    if triangles.is_empty() || (covered - expected).abs() > expected.abs() * 1e-3 {
+        None
+    } else {
+        Some(triangles)
+    }
+}
+
+/// Whether `p` lies inside or on the edge of the counter-clockwise triangle `a`, `b`, `c`.
+fn in_triangle(p: ovrVector2f, a: ovrVector2f, b: ovrVector2f, c: ovrVector2f) -> bool {
+    cross2(sub2(b, a), sub2(p, a)) >= 0.0 && cross2(sub2(c, b), sub2(p, b)) >= 0.0 && cross2(sub2(a, c), sub2(p, c)) >= 0.0
+}
+
+#[cfg(test)]
+fn face_normal(mesh: &BoundaryMesh, triangle: &[u32]) -> ovrVector3f {
+    let p = |i: u32| mesh.vertices[i as usize].position;
+    let (a, b, c) = (p(triangle[0]), p(triangle[1]), p(triangle[2]));
+    let (u, v) = (vec3(b.x - a.x, b.y - a.y, b.z - a.z), vec3(c.x - a.x, c.y - a.y, c.z - a.z));
+    vec3(u.y * v.z - u.z * v.y, u.z * v.x - u.x * v.z, u.x * v.y - u.y * v.x)
+}
+
+#[cfg(test)]
+#[test]
+fn test_floor_mesh() {
+    // A clockwise L shaped room, as the runtime reports it.
+    let point = |x, z| vec3(x, 0.5, z);
+    let room = BoundaryPolygon::new(vec![point(0.0, 0.0), point(0.0, 4.0), point(2.0, 4.0), point(2.0, 2.0), point(3.0, 2.0), point(4.0, 2.0), point(4.0, 0.0)]);
+    let color = ovrColorf { _align: [], r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+    let mesh = BoundaryMesh::floor(&room, &room, color).unwrap();
+    let mut area = 0.0;
+    for triangle in mesh.indices.chunks(3) {
+        let normal = face_normal(&mesh, triangle);
+        assert!(normal.y > 0.0);
+        area += normal.y / 2.0;
+    }
+    assert!((area - room.area()).abs() < 1e-4);
+    assert!(mesh.vertices.iter().all(|v| v.position.y == 0.5 && v.distance == 0.0));
+
+    let bow_tie = BoundaryPolygon::new(vec![point(0.0, 0.0), point(1.0, 1.0), point(1.0, 0.0), point(0.0, 1.0)]);
+    assert!(BoundaryMesh::floor(&bow_tie, &room, color).is_none());
+    assert!(BoundaryMesh::floor(&BoundaryPolygon::new(vec![point(0.0, 0.0), point(1.0, 0.0)]), &room, color).is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_wall_mesh() {
+    let point = |x, z| vec3(x, 0.0, z);
+    let outer = BoundaryPolygon::new(vec![point(0.0, 0.0), point(0.0, 1.0), point(1.0, 1.0), point(1.0, 0.0)]);
+    let inner = BoundaryPolygon::new(vec![point(0.25, 0.25), point(0.25, 0.75), point(0.75, 0.75), point(0.75, 0.25)]);
+    let color = ovrColorf { _align: [], r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+    let mesh = BoundaryMesh::walls(&outer, &inner, 2.0, color);
+    // Four 1m walls split into 0.25m quads.
+    assert_eq!(mesh.indices.len(), 4 * 4 * 6);
+    for triangle in mesh.indices.chunks(3) {
+        let normal = face_normal(&mesh, triangle);
+        let vertex = mesh.vertices[triangle[0] as usize];
+        assert!(normal.x * vertex.normal.x + normal.z * vertex.normal.z > 0.0);
+        // Facing the center of the room.
+        assert!(vertex.normal.x * (0.5 - vertex.position.x) + vertex.normal.z * (0.5 - vertex.position.z) > 0.0);
+    }
+    assert_eq!(mesh.vertices.iter().map(|v| v.uv.x).fold(0.0, f32::max), 4.0);
+    assert!(mesh.vertices.iter().all(|v| v.uv.y == v.position.y / 2.0));
+    assert!(mesh.vertices.iter().all(|v| v.distance >= 0.25 && v.distance <= 0.25 * 2.0f32.sqrt() + 1e-6));
+}
@@ -0,0 +1,213 @@
+use ::std::mem;
+use ::std::process;
+use ::std::ptr;
+
+use ::libc::c_int;
+
+use ::{
+    ovrBool,
+    ovrBoundaryLookAndFeel,
+    ovrBoundaryType,
+    ovrBoundary_Outer,
+    ovrBoundary_PlayArea,
+    ovrColorf,
+    ovrFalse,
+    ovrResult,
+    ovrSuccess_BoundaryInvalid,
+    ovrTrue,
+    ovrVector3f,
+    ovr_GetBoundaryDimensions,
+    ovr_GetBoundaryGeometry,
+    ovr_GetBoundaryVisible,
+    ovr_RequestBoundaryVisible,
+    ovr_ResetBoundaryLookAndFeel,
+    ovr_SetBoundaryLookAndFeel
+};
+use ::error::{
+    check_call,
+    Result
+};
+use ::session::Session;
+
+mod geometry;
+mod mesh;
+mod monitor;
+
+pub use self::geometry::{
+    BoundaryPolygon,
+    BoundaryRect,
+    BoundaryTest
+};
+pub use self::mesh::{
+    BoundaryMesh,
+    BoundaryVertex
+};
+pub use self::monitor::{
+    BoundaryEvent,
+    BoundaryMonitor,
+    DeviceProximity,
+    MonitorConfig,
+    ProximityEvent,
+    ProximityZone
+};
+
+/// The two boundaries set up by the user in the Guardian system.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BoundaryType {
+    /// The outer boundary, closely following the walls the user traced.
+    Outer,
+    /// The rectangular play area inside the outer boundary.
+    PlayArea,
+}
+
+impl BoundaryType {
+    /// The corresponding `ovrBoundaryType`.
+    pub fn as_raw(&self) -> ovrBoundaryType {
+        match *self {
+            BoundaryType::Outer => ovrBoundary_Outer,
+            BoundaryType::PlayArea => ovrBoundary_PlayArea,
+        }
+    }
+}
+
+/// Size in meters of the axis aligned bounding box enclosing a boundary.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundaryDimensions {
+    /// Extent along the X axis.
+    pub width: f32,
+    /// Extent along the Y axis.
+    pub height: f32,
+    /// Extent along the Z axis.
+    pub length: f32,
+}
+
+/// Safe access to the Boundary (Guardian) system of a session.
+///
+/// Queries that depend on the boundary return `Ok(None)` when the runtime reports
+/// `ovrSuccess_BoundaryInvalid`, meaning the user has not set up a boundary, rather than an error.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::boundary::BoundaryType;
+/// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+/// match session.boundary().geometry(BoundaryType::PlayArea)? {
+///     Some(points) => println!("play area has {} corners", points.len()),
+///     None => println!("Guardian is not set up"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Boundary {
+    session: Session,
+}
+
+impl Session {
+    /// Returns the boundary API for this session.
+    pub fn boundary(&self) -> Boundary {
+        Boundary {
+            session: *self,
+        }
+    }
+}
+
+/// Maps `ovrSuccess_BoundaryInvalid` to `None`, and any other success to `Some(value)`.
+fn valid<T>(result: ovrResult, value: T) -> Option<T> {
+    if result == ovrSuccess_BoundaryInvalid {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+impl Boundary {
+    /// The session this boundary belongs to.
+    pub fn session(&self) -> Session {
+        self.session
+    }
+
+    /// Gets the floor points of a boundary, in clockwise order, using `ovr_GetBoundaryGeometry`.
+    ///
+    /// `ovr_GetBoundaryGeometry` is not told the size of the buffer it fills, so the points are
+    /// read into a buffer with room for twice the number first reported, and at least 256. This
+    /// assumes the Guardian is not edited beyond that between the two calls. If the runtime reports
+    /// having written more points than fit, memory has already been overwritten and the process is
+    /// aborted.
+    pub fn geometry(&self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>> {
+        let session = self.session.as_raw();
+        let mut count: c_int = 0;
+        let result = check_call("ovr_GetBoundaryGeometry", || format!("{:?}, null", boundary), unsafe { ovr_GetBoundaryGeometry(session, boundary.as_raw(), ptr::null_mut(), &mut count as *mut _) })?;
+        if result == ovrSuccess_BoundaryInvalid {
+            return Ok(None);
+        }
+        let capacity = (count.max(0) as usize).saturating_mul(2).max(256);
+        let mut points: Vec<ovrVector3f> = Vec::with_capacity(capacity);
+        let mut filled: c_int = 0;
+        let result = check_call("ovr_GetBoundaryGeometry", || format!("{:?}, {} points", boundary, capacity), unsafe { ovr_GetBoundaryGeometry(session, boundary.as_raw(), points.as_mut_ptr(), &mut filled as *mut _) })?;
+        let filled = filled.max(0) as usize;
+        if filled > capacity {
+            eprintln!("ovr_GetBoundaryGeometry wrote {} points into a buffer of {}, aborting", filled, capacity);
+            process::abort();
+        }
+        unsafe { points.set_len(filled) };
+        Ok(valid(result, points))
+    }
+
+    /// Gets a boundary as a polygon for offline analysis, see [`BoundaryPolygon`](struct.BoundaryPolygon.html).
+    pub fn polygon(&self, boundary: BoundaryType) -> Result<Option<BoundaryPolygon>> {
+        Ok(self.geometry(boundary)?.map(BoundaryPolygon::new))
+    }
+
+    /// Gets the floor points of the outer boundary.
+    pub fn outer_geometry(&self) -> Result<Option<Vec<ovrVector3f>>> {
+        self.geometry(BoundaryType::Outer)
+    }
+
+    /// Gets the floor points of the play area.
+    pub fn play_area_geometry(&self) -> Result<Option<Vec<ovrVector3f>>> {
+        self.geometry(BoundaryType::PlayArea)
+    }
+
+    /// Gets the size of the bounding box of a boundary using `ovr_GetBoundaryDimensions`.
+    pub fn dimensions(&self, boundary: BoundaryType) -> Result<Option<BoundaryDimensions>> {
+        let mut dimensions: ovrVector3f = unsafe { mem::zeroed() };
+        let result = check_call("ovr_GetBoundaryDimensions", || format!("{:?}", boundary), unsafe { ovr_GetBoundaryDimensions(self.session.as_raw(), boundary.as_raw(), &mut dimensions as *mut _) })?;
+        Ok(valid(result, BoundaryDimensions {
+            width: dimensions.x,
+            height: dimensions.y,
+            length: dimensions.z,
+        }))
+    }
+
+    /// Returns whether the boundary is currently visible, using `ovr_GetBoundaryVisible`.
+    pub fn is_visible(&self) -> Result<Option<bool>> {
+        let mut visible: ovrBool = ovrFalse;
+        let result = check_call("ovr_GetBoundaryVisible", String::new, unsafe { ovr_GetBoundaryVisible(self.session.as_raw(), &mut visible as *mut _) })?;
+        Ok(valid(result, visible != ovrFalse))
+    }
+
+    /// Requests that the outer boundary be shown, or cancels a previous request.
+    ///
+    /// The application cannot hide the boundary, only withdraw its own request to show it.
+    pub fn request_visible(&self, visible: bool) -> Result<()> {
+        check_call("ovr_RequestBoundaryVisible", || visible.to_string(), unsafe { ovr_RequestBoundaryVisible(self.session.as_raw(), if visible { ovrTrue } else { ovrFalse }) })?;
+        Ok(())
+    }
+
+    /// Sets the colour of the runtime's boundary using `ovr_SetBoundaryLookAndFeel`. The alpha channel is ignored.
+    ///
+    /// Pair this with [`BoundaryMesh::color`](struct.BoundaryMesh.html#structfield.color) so custom walls match the runtime's.
+    pub fn set_look_and_feel(&self, color: ovrColorf) -> Result<()> {
+        let look = ovrBoundaryLookAndFeel {
+            Color: color,
+        };
+        check_call("ovr_SetBoundaryLookAndFeel", || format!("{:?}", color), unsafe { ovr_SetBoundaryLookAndFeel(self.session.as_raw(), &look as *const _) })?;
+        Ok(())
+    }
+
+    /// Restores the runtime's default boundary look and feel using `ovr_ResetBoundaryLookAndFeel`.
+    pub fn reset_look_and_feel(&self) -> Result<()> {
+        check_call("ovr_ResetBoundaryLookAndFeel", String::new, unsafe { ovr_ResetBoundaryLookAndFeel(self.session.as_raw()) })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+use ::{
+    ovrBoundaryTestResult,
+    ovrError_InvalidOperation,
+    ovrFalse
+};
+use ::error::{
+    Error,
+    Result
+};
+use ::session::Session;
+use ::tracking::{
+    TrackedDevice,
+    TrackingSource
+};
+use super::BoundaryType;
+
+/// Distance thresholds used by a [`BoundaryMonitor`](struct.BoundaryMonitor.html), in meters.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MonitorConfig {
+    /// The boundary to test against.
+    pub boundary: BoundaryType,
+    /// Distance at which a device starts approaching the boundary.
+    pub approach_distance: f32,
+    /// Distance at which a device enters the warning zone. A device also enters the warning zone
+    /// while the runtime reports that the boundary is triggering.
+    pub warning_distance: f32,
+    /// Extra distance a device must move away before it leaves a zone, so that devices hovering
+    /// at a threshold do not produce a stream of events.
+    pub hysteresis: f32,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> MonitorConfig {
+        MonitorConfig {
+            boundary: BoundaryType::PlayArea,
+            approach_distance: 0.5,
+            warning_distance: 0.15,
+            hysteresis: 0.05,
+        }
+    }
+}
+
+/// How close a device is to the boundary, ordered from furthest to closest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProximityZone {
+    /// Further than the approach distance.
+    Clear,
+    /// Within the approach distance.
+    Approaching,
+    /// Within the warning distance, or triggering the runtime's boundary.
+    Warning,
+}
+
+/// Kinds of [`BoundaryEvent`](struct.BoundaryEvent.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProximityEvent {
+    /// The device moved from `Clear` to `Approaching`.
+    Approach,
+    /// The device moved into `Warning`.
+    Enter,
+    /// The device moved out of `Warning`.
+    Exit,
+    /// The device moved from `Approaching` back to `Clear`.
+    Recede,
+}
+
+/// A change in how close a device is to the boundary.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundaryEvent {
+    /// The device that moved.
+    pub device: TrackedDevice,
+    /// What happened.
+    pub kind: ProximityEvent,
+    /// Distance from the device to the boundary when the event happened.
+    pub distance: f32,
+}
+
+/// The latest proximity of one monitored device.
+#[derive(Debug, Copy, Clone)]
+pub struct DeviceProximity {
+    /// The device.
+    pub device: TrackedDevice,
+    /// The zone the device is in.
+    pub zone: ProximityZone,
+    /// The latest test result, or `None` if the device has not been tested successfully yet or
+    /// was unavailable at the last poll.
+    pub result: Option<ovrBoundaryTestResult>,
+}
+
+/// Polls `ovr_TestBoundary` for a set of devices and reports when they approach or enter the
+/// warning zone near the boundary.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::boundary::{BoundaryMonitor, MonitorConfig, ProximityEvent};
+/// # use ovr_sys::tracking::TrackedDevice;
+/// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+/// let mut monitor = BoundaryMonitor::new(session, &TrackedDevice::ALL, MonitorConfig::default());
+/// loop {
+///     for event in monitor.poll()? {
+///         if event.kind == ProximityEvent::Enter {
+///             println!("{:?} is close to the boundary", event.device);
+///         }
+///     }
+///     // ... render the frame ...
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundaryMonitor {
+    session: Option<Session>,
+    config: MonitorConfig,
+    devices: Vec<DeviceProximity>,
+}
+
+impl BoundaryMonitor {
+    /// Creates a monitor for `devices`, all starting in the `Clear` zone.
+    pub fn new(session: Session, devices: &[TrackedDevice], config: MonitorConfig) -> BoundaryMonitor {
+        let mut monitor = BoundaryMonitor::without_session(devices, config);
+        monitor.session = Some(session);
+        monitor
+    }
+
+    /// Creates a monitor that is not tied to a session, for use with
+    /// [`poll_from`](#method.poll_from) or [`update`](#method.update) only. `poll` fails with
+    /// `ovrError_InvalidOperation`.
+    pub fn without_session(devices: &[TrackedDevice], config: MonitorConfig) -> BoundaryMonitor {
+        let mut monitored: Vec<DeviceProximity> = Vec::with_capacity(devices.len());
+        for &device in devices {
+            if monitored.iter().all(|d| d.device != device) {
+                monitored.push(DeviceProximity {
+                    device,
+                    zone: ProximityZone::Clear,
+                    result: None,
+                });
+            }
+        }
+        BoundaryMonitor {
+            session: None,
+            config,
+            devices: monitored,
+        }
+    }
+
+    /// The thresholds in use.
+    pub fn config(&self) -> &MonitorConfig {
+        &self.config
+    }
+
+    /// Changes the thresholds. Devices stay in their current zones until the next update.
+    pub fn set_config(&mut self, config: MonitorConfig) {
+        self.config = config;
+    }
+
+    /// The latest proximity of every monitored device.
+    pub fn devices(&self) -> &[DeviceProximity] {
+        &self.devices
+    }
+
+    /// The latest proximity of one device, if it is monitored.
+    pub fn device(&self, device: TrackedDevice) -> Option<&DeviceProximity> {
+        self.devices.iter().find(|d| d.device == device)
+    }
+
+    /// The monitored device closest to the boundary, for example to fade the scene by distance.
+    pub fn closest(&self) -> Option<&DeviceProximity> {
+        self.devices.iter()
+            .filter(|d| d.result.is_some())
+            .fold(None, |closest: Option<&DeviceProximity>, d| match closest {
+                Some(c) if c.result.unwrap().ClosestDistance <= d.result.unwrap().ClosestDistance => Some(c),
+                _ => Some(d),
+            })
+    }
+
+    /// The closest zone of any monitored device.
+    pub fn zone(&self) -> ProximityZone {
+        self.devices.iter().map(|d| d.zone).max().unwrap_or(ProximityZone::Clear)
+    }
+
+    /// Tests every monitored device against the boundary, returning the resulting events.
+    ///
+    /// Devices that are not currently tracked, and every device while the boundary is not set up,
+    /// keep their zone but have their result cleared.
+    pub fn poll(&mut self) -> Result<Vec<BoundaryEvent>> {
+        let mut session = self.session.ok_or_else(|| Error::new(ovrError_InvalidOperation, "boundary monitor has no session"))?;
+        self.poll_from(&mut session)
+    }
+
+    /// Tests every monitored device against the boundary of any tracking source, as `poll` does
+    /// for the session of the monitor.
+    ///
+    /// This allows driving the monitor from a recording, see [`Player`](../replay/struct.Player.html).
+    pub fn poll_from<S: TrackingSource + ?Sized>(&mut self, source: &mut S) -> Result<Vec<BoundaryEvent>> {
+        let mut events = Vec::new();
+        for i in 0..self.devices.len() {
+            let device = self.devices[i].device;
+            let result = source.test_boundary(device, self.config.boundary)?;
+            events.extend(self.update(device, result));
+        }
+        Ok(events)
+    }
+
+    /// Feeds a test result for one device, as `poll` does, returning the resulting event if any.
+    ///
+    /// This allows driving the monitor from recorded or simulated results. Results for devices that
+    /// are not monitored are ignored.
+    pub fn update(&mut self, device: TrackedDevice, result: Option<ovrBoundaryTestResult>) -> Option<BoundaryEvent> {
+        let config = self.config;
+        let state = self.devices.iter_mut().find(|d| d.device == device)?;
+        state.result = result;
+        let result = result?;
+        let zone = next_zone(&config, state.zone, &result);
+        let kind = match (state.zone, zone) {
+            (from, to) if from == to => None,
+            (_, ProximityZone::Warning) => Some(ProximityEvent::Enter),
+            (ProximityZone::Warning, _) => Some(ProximityEvent::Exit),
+            (_, ProximityZone::Approaching) => Some(ProximityEvent::Approach),
+            (_, ProximityZone::Clear) => Some(ProximityEvent::Recede),
+        };
+        state.zone = zone;
+        kind.map(|kind| BoundaryEvent {
+            device,
+            kind,
+            distance: result.ClosestDistance,
+        })
+    }
+}
+
+/// The zone a device is in after a test, given the zone it was in before.
+fn next_zone(config: &MonitorConfig, zone: ProximityZone, result: &ovrBoundaryTestResult) -> ProximityZone {
+    let margin = |inside: bool| if inside { config.hysteresis } else { 0.0 };
+    let distance = result.ClosestDistance;
+    if result.IsTriggering != ovrFalse || distance <= config.warning_distance + margin(zone == ProximityZone::Warning) {
+        ProximityZone::Warning
+    } else if distance <= config.approach_distance + margin(zone >= ProximityZone::Approaching) {
+        ProximityZone::Approaching
+    } else {
+        ProximityZone::Clear
+    }
+}
+
+#[cfg(test)]
+fn test_result(distance: f32, triggering: bool) -> ovrBoundaryTestResult {
+    let mut result: ovrBoundaryTestResult = unsafe { ::std::mem::zeroed() };
+    result.ClosestDistance = distance;
+    result.IsTriggering = if triggering { ::ovrTrue } else { ovrFalse };
+    result
+}
+
+#[cfg(test)]
+#[test]
+fn test_monitor_events() {
+    let mut monitor = BoundaryMonitor::without_session(&[TrackedDevice::RTouch, TrackedDevice::RTouch], MonitorConfig::default());
+    assert_eq!(monitor.devices().len(), 1);
+    let mut step = |distance: f32, triggering: bool| {
+        monitor.update(TrackedDevice::RTouch, Some(test_result(distance, triggering))).map(|e| e.kind)
+    };
+    assert_eq!(step(1.0, false), None);
+    assert_eq!(step(0.45, false), Some(ProximityEvent::Approach));
+    // Hovering around the approach threshold stays within the hysteresis band.
+    assert_eq!(step(0.52, false), None);
+    assert_eq!(step(0.49, false), None);
+    assert_eq!(step(0.1, false), Some(ProximityEvent::Enter));
+    assert_eq!(step(0.18, false), None);
+    assert_eq!(step(0.25, false), Some(ProximityEvent::Exit));
+    assert_eq!(step(0.4, true), Some(ProximityEvent::Enter));
+    assert_eq!(step(2.0, false), Some(ProximityEvent::Exit));
+    assert_eq!(step(0.3, false), Some(ProximityEvent::Approach));
+    assert_eq!(step(0.6, false), Some(ProximityEvent::Recede));
+
+    // Untracked devices keep their zone.
+    assert_eq!(monitor.update(TrackedDevice::RTouch, Some(test_result(0.3, false))).map(|e| e.kind), Some(ProximityEvent::Approach));
+    assert_eq!(monitor.update(TrackedDevice::RTouch, None), None);
+    assert_eq!(monitor.zone(), ProximityZone::Approaching);
+    assert!(monitor.closest().is_none());
+    assert_eq!(monitor.update(TrackedDevice::Hmd, Some(test_result(0.0, true))), None);
+}
@@ -0,0 +1,257 @@
+use ::std::collections::VecDeque;
+use ::std::error;
+use ::std::ffi::CStr;
+use ::std::fmt;
+use ::std::mem;
+use ::std::sync::Mutex;
+use ::std::thread;
+use ::std::time::{
+    SystemTime,
+    UNIX_EPOCH
+};
+
+use ::libc::c_char;
+
+use ::{
+    ovrErrorInfo,
+    ovrResult,
+    ovrSuccess,
+    ovr_GetLastErrorInfo,
+    OVR_FAILURE
+};
+
+/// An error returned by a LibOVR call.
+///
+/// Combines the failing `ovrResult` with the message reported by `ovr_GetLastErrorInfo` at the
+/// time of the failure.
+///
+/// see [`ovr_GetLastErrorInfo`](../fn.ovr_GetLastErrorInfo.html)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    result: ovrResult,
+    message: String,
+}
+
+impl Error {
+    /// Creates an error from a result code and a message.
+    pub fn new<S: Into<String>>(result: ovrResult, message: S) -> Error {
+        Error {
+            result,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for a failed `result`, taking the message from `ovr_GetLastErrorInfo`.
+    ///
+    /// This must be called immediately after the failing call, with no other LibOVR calls in between,
+    /// otherwise the message may belong to a different failure.
+    pub fn last(result: ovrResult) -> Error {
+        let info = unsafe {
+            let mut info: ovrErrorInfo = mem::zeroed();
+            ovr_GetLastErrorInfo(&mut info as *mut _);
+            info
+        };
+        Error::new(result, error_string(&info))
+    }
+
+    /// The `ovrResult` of the failed call.
+    pub fn result(&self) -> ovrResult {
+        self.result
+    }
+
+    /// The English description of the failure, which may be empty.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+fn error_string(info: &ovrErrorInfo) -> String {
+    let message = unsafe { CStr::from_ptr(&info.ErrorString as *const c_char) };
+    message.to_string_lossy().into_owned()
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.message.is_empty() {
+            write!(fmt, "LibOVR error {}", self.result)
+        } else {
+            write!(fmt, "{} (LibOVR error {})", self.message, self.result)
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Result type used by the safe wrappers in this crate.
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// Converts an `ovrResult` into a `Result`.
+///
+/// Successful results, including qualified successes such as `ovrSuccess_NotVisible`, are passed
+/// through so that the caller can distinguish them. Failures are turned into an [`Error`](struct.Error.html)
+/// using `Error::last`.
+pub fn check(result: ovrResult) -> Result<ovrResult> {
+    if OVR_FAILURE(result) {
+        Err(Error::last(result))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Number of calls kept in the error history unless changed with
+/// [`set_error_history_capacity`](fn.set_error_history_capacity.html).
+pub const DEFAULT_ERROR_HISTORY_CAPACITY: usize = 64;
+
+/// A LibOVR call that failed or returned a qualified success, as kept in the error history.
+///
+/// Only calls made through the safe wrappers of this crate are recorded, unless the `trace`
+/// feature is enabled, which records calls made directly to the `ovr_*` functions too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallRecord {
+    /// The name of the LibOVR function.
+    pub function: &'static str,
+    /// A summary of the arguments of the call.
+    pub arguments: String,
+    /// The `ovrResult` returned.
+    pub result: ovrResult,
+    /// The `ovrErrorInfo::ErrorString` reported after the call, which may be empty.
+    pub message: String,
+    /// When the call returned.
+    pub time: SystemTime,
+    /// The name of the calling thread, if it has one.
+    pub thread: Option<String>,
+}
+
+impl fmt::Display for CallRecord {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let since_epoch = self.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(fmt, "[{}.{:03}] {}({}) -> {}", since_epoch.as_secs(), since_epoch.subsec_millis(), self.function, self.arguments, self.result)?;
+        if !self.message.is_empty() {
+            write!(fmt, " {}", self.message)?;
+        }
+        if let Some(ref thread) = self.thread {
+            write!(fmt, " (thread {})", thread)?;
+        }
+        Ok(())
+    }
+}
+
+struct ErrorHistory {
+    records: VecDeque<CallRecord>,
+    capacity: usize,
+}
+
+static HISTORY: Mutex<ErrorHistory> = Mutex::new(ErrorHistory {
+    records: VecDeque::new(),
+    capacity: DEFAULT_ERROR_HISTORY_CAPACITY,
+});
+
+fn with_history<T, F: FnOnce(&mut ErrorHistory) -> T>(f: F) -> T {
+    f(&mut HISTORY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+/// Converts the `ovrResult` of a LibOVR call into a `Result` like [`check`](fn.check.html), also
+/// recording failures and qualified successes in the error history.
+///
+/// `arguments` summarises the arguments of the call, and is only evaluated when the call is
+/// recorded. The safe wrappers in this crate go through this function. With the `trace` feature,
+/// the wrapper of every LibOVR function returning an `ovrResult` records the call instead, so that
+/// calls made directly to the `ovr_*` functions are recorded too, and this function records nothing.
+pub fn check_call<F: FnOnce() -> String>(function: &'static str, arguments: F, result: ovrResult) -> Result<ovrResult> {
+    if result == ovrSuccess {
+        return Ok(result);
+    }
+    // Qualified successes may also leave a message, so it is read for any result but `ovrSuccess`.
+    let error = Error::last(result);
+    if cfg!(not(feature = "trace")) {
+        record_call(function, arguments(), result, error.message().to_string());
+    }
+    if OVR_FAILURE(result) {
+        Err(error)
+    } else {
+        Ok(result)
+    }
+}
+
+/// Records a call made through a wrapper generated by the `trace` feature, unless it returned
+/// `ovrSuccess`.
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+pub fn record_traced_call<F: FnOnce() -> String>(function: &'static str, arguments: F, result: ovrResult) {
+    if result == ovrSuccess {
+        return;
+    }
+    // Read through the untraced function, so that keeping the history does not add to the trace.
+    let info = unsafe {
+        let mut info: ovrErrorInfo = mem::zeroed();
+        ::raw::ovr_GetLastErrorInfo(&mut info as *mut _);
+        info
+    };
+    record_call(function, arguments(), result, error_string(&info));
+}
+
+fn record_call(function: &'static str, arguments: String, result: ovrResult, message: String) {
+    let record = CallRecord {
+        function,
+        arguments,
+        result,
+        message,
+        time: SystemTime::now(),
+        thread: thread::current().name().map(|name| name.to_string()),
+    };
+    with_history(|history| {
+        if history.capacity == 0 {
+            return;
+        }
+        while history.records.len() >= history.capacity {
+            history.records.pop_front();
+        }
+        history.records.push_back(record);
+    });
+}
+
+/// The recorded failures and qualified successes, oldest first.
+pub fn error_history() -> Vec<CallRecord> {
+    with_history(|history| history.records.iter().cloned().collect())
+}
+
+/// Formats the error history one call per line, oldest first, for inclusion in crash reports.
+pub fn dump_error_history() -> String {
+    with_history(|history| history.records.iter().map(|record| format!("{}\n", record)).collect())
+}
+
+/// Forgets the recorded calls.
+pub fn clear_error_history() {
+    with_history(|history| history.records.clear());
+}
+
+/// Sets how many calls the error history keeps, dropping the oldest ones if needed. Zero disables
+/// recording.
+pub fn set_error_history_capacity(capacity: usize) {
+    with_history(|history| {
+        history.capacity = capacity;
+        while history.records.len() > capacity {
+            history.records.pop_front();
+        }
+    });
+}
+
+#[cfg(test)]
+#[test]
+fn test_error_history() {
+    set_error_history_capacity(2);
+    record_call("ovr_GetBoundaryGeometry", "PlayArea".to_string(), ::ovrSuccess_BoundaryInvalid, String::new());
+    record_call("ovr_GetPerfStats", String::new(), ::ovrError_ServiceError, "Service connection lost".to_string());
+    record_call("ovr_TestBoundary", "Hmd, Outer".to_string(), ::ovrError_InvalidSession, String::new());
+    let history = error_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].function, "ovr_GetPerfStats");
+    let dump = dump_error_history();
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with("] ovr_GetPerfStats() -> -1006 Service connection lost (thread error::test_error_history)"));
+    assert!(lines[1].contains("] ovr_TestBoundary(Hmd, Outer) -> -1002"));
+    clear_error_history();
+    assert!(error_history().is_empty());
+    set_error_history_capacity(DEFAULT_ERROR_HISTORY_CAPACITY);
+}
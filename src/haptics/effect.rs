@@ -0,0 +1,478 @@
+use ::std::collections::BTreeMap;
+use ::std::error;
+use ::std::f32::consts::PI;
+use ::std::fmt;
+use ::std::str::FromStr;
+
+use ::{
+    ovrError_DeviceUnavailable,
+    ovrTouchHapticsDesc
+};
+use ::error::Error;
+use super::{
+    encode_samples,
+    has_haptics_engine
+};
+
+/// Length of a [`HapticEffect::Click`](enum.HapticEffect.html#variant.Click) in seconds.
+pub const CLICK_DURATION: f32 = 0.025;
+/// Length of a [`HapticEffect::Tick`](enum.HapticEffect.html#variant.Tick) in seconds.
+pub const TICK_DURATION: f32 = 0.01;
+/// Longest effect, in seconds, accepted by the text form and rendered by
+/// [`HapticEffect::render`](enum.HapticEffect.html#method.render). Longer effects are cut short.
+pub const MAX_EFFECT_DURATION: f32 = 60.0;
+
+/// An attack/decay/sustain/release amplitude envelope.
+///
+/// Times are in seconds, `sustain` is the level held between the end of the decay and the start of
+/// the release, as a fraction of the peak amplitude.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    /// The envelope level at `t` seconds into a sound lasting `duration` seconds.
+    pub fn level(&self, t: f32, duration: f32) -> f32 {
+        let release_start = (duration - self.release).max(0.0);
+        let held = if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+        if t >= release_start && self.release > 0.0 {
+            held * (1.0 - (t - release_start) / self.release).max(0.0)
+        } else {
+            held
+        }
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Envelope {
+        Envelope {
+            attack: 0.0,
+            decay: 0.0,
+            sustain: 1.0,
+            release: 0.0,
+        }
+    }
+}
+
+/// A parametric haptic effect that can be rendered into Touch haptics samples.
+///
+/// All amplitudes are in the `[0.0, 1.0]` range and all durations are in seconds. Effects render
+/// to amplitude samples with [`render`](#method.render), or straight to the opaque sample format of a
+/// controller with [`render_for`](#method.render_for), so the output can be inspected without any
+/// hardware attached.
+///
+/// Effects have a one line text form, as used by [`EffectLibrary`](struct.EffectLibrary.html), made of
+/// the effect name followed by `key=value` parameters. Missing parameters take their default value.
+///
+/// ```
+/// # use ovr_sys::haptics::HapticEffect;
+/// let effect: HapticEffect = "buzz amplitude=0.5 duration=0.2".parse().unwrap();
+/// assert_eq!(effect, HapticEffect::Buzz { amplitude: 0.5, duration: 0.2 });
+/// assert_eq!(effect.render(320).len(), 64);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HapticEffect {
+    /// A short, sharp pulse that decays over `CLICK_DURATION`.
+    Click { amplitude: f32 },
+    /// A single flat pulse lasting `TICK_DURATION`, lighter than a click.
+    Tick { amplitude: f32 },
+    /// Constant vibration.
+    Buzz { amplitude: f32, duration: f32 },
+    /// A "lub-dub" double pulse repeated `beats` times at `bpm` beats per minute.
+    Heartbeat { amplitude: f32, bpm: f32, beats: u32 },
+    /// Linear ramp from one amplitude to another.
+    Ramp { from: f32, to: f32, duration: f32 },
+    /// Vibration shaped by an envelope, optionally pulsing at `frequency` Hz (0 for no pulsing).
+    ///
+    /// Frequencies above half of the controller sample rate cannot be represented.
+    Tone { amplitude: f32, frequency: f32, duration: f32, envelope: Envelope },
+}
+
+impl HapticEffect {
+    /// The length of the effect in seconds.
+    pub fn duration(&self) -> f32 {
+        match *self {
+            HapticEffect::Click { .. } => CLICK_DURATION,
+            HapticEffect::Tick { .. } => TICK_DURATION,
+            HapticEffect::Buzz { duration, .. } |
+            HapticEffect::Ramp { duration, .. } |
+            HapticEffect::Tone { duration, .. } => duration.max(0.0),
+            HapticEffect::Heartbeat { bpm, beats, .. } => if bpm > 0.0 { beats as f32 * 60.0 / bpm } else { 0.0 },
+        }
+    }
+
+    /// The amplitude of the effect `t` seconds after it starts.
+    pub fn amplitude_at(&self, t: f32) -> f32 {
+        let duration = self.duration();
+        if t < 0.0 || t >= duration {
+            return 0.0;
+        }
+        let value = match *self {
+            HapticEffect::Click { amplitude } => amplitude * (1.0 - t / CLICK_DURATION),
+            HapticEffect::Tick { amplitude } |
+            HapticEffect::Buzz { amplitude, .. } => amplitude,
+            HapticEffect::Heartbeat { amplitude, bpm, .. } => {
+                let phase = t % (60.0 / bpm);
+                if phase < 0.08 {
+                    amplitude
+                } else if (0.18..0.24).contains(&phase) {
+                    amplitude * 0.6
+                } else {
+                    0.0
+                }
+            }
+            HapticEffect::Ramp { from, to, duration } => from + (to - from) * t / duration,
+            HapticEffect::Tone { amplitude, frequency, duration, envelope } => {
+                let pulse = if frequency > 0.0 { 0.5 - 0.5 * (2.0 * PI * frequency * t).cos() } else { 1.0 };
+                amplitude * envelope.level(t, duration) * pulse
+            }
+        };
+        value.clamp(0.0, 1.0)
+    }
+
+    /// Renders the effect into amplitude samples at `sample_rate` Hz.
+    ///
+    /// Any effect with a non-zero duration produces at least one sample, and effects longer than
+    /// [`MAX_EFFECT_DURATION`](constant.MAX_EFFECT_DURATION.html) are cut short.
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let rate = sample_rate as f32;
+        let count = (self.duration().min(MAX_EFFECT_DURATION) * rate).ceil() as usize;
+        (0..count).map(|i| self.amplitude_at(i as f32 / rate)).collect()
+    }
+
+    /// Renders the effect in the sample rate and sample format of a controller, ready to be used as
+    /// the data of an `ovrHapticsBuffer`.
+    ///
+    /// Fails with `ovrError_DeviceUnavailable` if `desc` describes no usable haptics engine, as
+    /// returned by `ovr_GetTouchHapticsDesc` for a controller that is not connected.
+    pub fn render_for(&self, desc: &ovrTouchHapticsDesc) -> ::error::Result<Vec<u8>> {
+        if !has_haptics_engine(desc) {
+            return Err(Error::new(ovrError_DeviceUnavailable, "no haptics engine described"));
+        }
+        let samples = self.render(desc.SampleRateHz as u32);
+        let mut out = Vec::new();
+        encode_samples(&samples, desc.SampleSizeInBytes as usize, &mut out);
+        Ok(out)
+    }
+}
+
+impl fmt::Display for HapticEffect {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HapticEffect::Click { amplitude } => write!(fmt, "click amplitude={}", amplitude),
+            HapticEffect::Tick { amplitude } => write!(fmt, "tick amplitude={}", amplitude),
+            HapticEffect::Buzz { amplitude, duration } => write!(fmt, "buzz amplitude={} duration={}", amplitude, duration),
+            HapticEffect::Heartbeat { amplitude, bpm, beats } => write!(fmt, "heartbeat amplitude={} bpm={} beats={}", amplitude, bpm, beats),
+            HapticEffect::Ramp { from, to, duration } => write!(fmt, "ramp from={} to={} duration={}", from, to, duration),
+            HapticEffect::Tone { amplitude, frequency, duration, envelope } => write!(
+                fmt, "tone amplitude={} frequency={} duration={} attack={} decay={} sustain={} release={}",
+                amplitude, frequency, duration, envelope.attack, envelope.decay, envelope.sustain, envelope.release),
+        }
+    }
+}
+
+/// An error from parsing a [`HapticEffect`](enum.HapticEffect.html) or an
+/// [`EffectLibrary`](struct.EffectLibrary.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEffectError {
+    message: String,
+}
+
+impl ParseEffectError {
+    fn new<S: Into<String>>(message: S) -> ParseEffectError {
+        ParseEffectError { message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseEffectError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&self.message)
+    }
+}
+
+impl error::Error for ParseEffectError {}
+
+struct Params<'a> {
+    effect: &'a str,
+    values: Vec<(&'a str, &'a str)>,
+    used: Vec<bool>,
+}
+
+impl<'a> Params<'a> {
+    fn get<T: FromStr>(&mut self, key: &str, default: T) -> Result<T, ParseEffectError> {
+        match self.values.iter().position(|&(k, _)| k == key) {
+            Some(index) => {
+                self.used[index] = true;
+                self.values[index].1.parse().map_err(|_| ParseEffectError::new(
+                    format!("invalid value `{}` for `{}` in {} effect", self.values[index].1, key, self.effect)))
+            }
+            None => Ok(default),
+        }
+    }
+
+    fn finish(self) -> Result<(), ParseEffectError> {
+        match self.used.iter().position(|&used| !used) {
+            Some(index) => Err(ParseEffectError::new(format!("unknown parameter `{}` for {} effect", self.values[index].0, self.effect))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks that the values of a parsed effect are finite, that durations and rates are not
+/// negative, and that the effect is no longer than `MAX_EFFECT_DURATION`.
+fn validate(effect: &HapticEffect) -> Result<(), ParseEffectError> {
+    let finite = |key: &str, value: f32| if value.is_finite() {
+        Ok(())
+    } else {
+        Err(ParseEffectError::new(format!("`{}` must be finite, found {}", key, value)))
+    };
+    let positive = |key: &str, value: f32| if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ParseEffectError::new(format!("`{}` must be finite and not negative, found {}", key, value)))
+    };
+    match *effect {
+        HapticEffect::Click { amplitude } | HapticEffect::Tick { amplitude } => finite("amplitude", amplitude)?,
+        HapticEffect::Buzz { amplitude, duration } => {
+            finite("amplitude", amplitude)?;
+            positive("duration", duration)?;
+        }
+        HapticEffect::Heartbeat { amplitude, bpm, .. } => {
+            finite("amplitude", amplitude)?;
+            positive("bpm", bpm)?;
+        }
+        HapticEffect::Ramp { from, to, duration } => {
+            finite("from", from)?;
+            finite("to", to)?;
+            positive("duration", duration)?;
+        }
+        HapticEffect::Tone { amplitude, frequency, duration, envelope } => {
+            finite("amplitude", amplitude)?;
+            positive("frequency", frequency)?;
+            positive("duration", duration)?;
+            positive("attack", envelope.attack)?;
+            positive("decay", envelope.decay)?;
+            positive("sustain", envelope.sustain)?;
+            positive("release", envelope.release)?;
+        }
+    }
+    let duration = effect.duration();
+    if duration.is_finite() && duration <= MAX_EFFECT_DURATION {
+        Ok(())
+    } else {
+        Err(ParseEffectError::new(format!("effect lasts {}s, longer than the maximum of {}s", duration, MAX_EFFECT_DURATION)))
+    }
+}
+
+impl FromStr for HapticEffect {
+    type Err = ParseEffectError;
+
+    fn from_str(s: &str) -> Result<HapticEffect, ParseEffectError> {
+        let mut words = s.split_whitespace();
+        let effect = words.next().ok_or_else(|| ParseEffectError::new("empty effect"))?;
+        let mut values = Vec::new();
+        for word in words {
+            let mut parts = word.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => values.push((key, value)),
+                _ => return Err(ParseEffectError::new(format!("expected `key=value`, found `{}`", word))),
+            }
+        }
+        let used = vec![false; values.len()];
+        let mut p = Params { effect, values, used };
+        let parsed = match effect {
+            "click" => HapticEffect::Click { amplitude: p.get("amplitude", 1.0)? },
+            "tick" => HapticEffect::Tick { amplitude: p.get("amplitude", 0.5)? },
+            "buzz" => HapticEffect::Buzz { amplitude: p.get("amplitude", 1.0)?, duration: p.get("duration", 0.1)? },
+            "heartbeat" => HapticEffect::Heartbeat {
+                amplitude: p.get("amplitude", 1.0)?,
+                bpm: p.get("bpm", 60.0)?,
+                beats: p.get("beats", 1)?,
+            },
+            "ramp" => HapticEffect::Ramp { from: p.get("from", 0.0)?, to: p.get("to", 1.0)?, duration: p.get("duration", 0.1)? },
+            "tone" => HapticEffect::Tone {
+                amplitude: p.get("amplitude", 1.0)?,
+                frequency: p.get("frequency", 0.0)?,
+                duration: p.get("duration", 0.1)?,
+                envelope: Envelope {
+                    attack: p.get("attack", 0.0)?,
+                    decay: p.get("decay", 0.0)?,
+                    sustain: p.get("sustain", 1.0)?,
+                    release: p.get("release", 0.0)?,
+                },
+            },
+            _ => return Err(ParseEffectError::new(format!("unknown effect `{}`", effect))),
+        };
+        p.finish()?;
+        validate(&parsed)?;
+        Ok(parsed)
+    }
+}
+
+/// A named collection of haptic effects, stored as text so that it can be authored by hand.
+///
+/// Each line holds `name = effect`, with the effect in the text form of
+/// [`HapticEffect`](enum.HapticEffect.html). Blank lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// # UI feedback
+/// button_press = click amplitude=0.8
+/// low_health = heartbeat amplitude=1 bpm=90 beats=4
+/// engine = tone amplitude=0.6 frequency=20 duration=2 attack=0.3 release=0.5
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectLibrary {
+    effects: BTreeMap<String, HapticEffect>,
+}
+
+impl EffectLibrary {
+    /// Creates an empty library.
+    pub fn new() -> EffectLibrary {
+        EffectLibrary::default()
+    }
+
+    /// Looks up an effect by name.
+    pub fn get(&self, name: &str) -> Option<&HapticEffect> {
+        self.effects.get(name)
+    }
+
+    /// Adds or replaces an effect, returning the previous effect with the same name.
+    pub fn insert<S: Into<String>>(&mut self, name: S, effect: HapticEffect) -> Option<HapticEffect> {
+        self.effects.insert(name.into(), effect)
+    }
+
+    /// Removes an effect by name.
+    pub fn remove(&mut self, name: &str) -> Option<HapticEffect> {
+        self.effects.remove(name)
+    }
+
+    /// Iterates over the effects in name order.
+    pub fn iter(&self) -> ::std::collections::btree_map::Iter<'_, String, HapticEffect> {
+        self.effects.iter()
+    }
+}
+
+impl fmt::Display for EffectLibrary {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for (name, effect) in &self.effects {
+            writeln!(fmt, "{} = {}", name, effect)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EffectLibrary {
+    type Err = ParseEffectError;
+
+    fn from_str(s: &str) -> Result<EffectLibrary, ParseEffectError> {
+        let mut library = EffectLibrary::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            let effect = parts.next().unwrap_or("").trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(ParseEffectError::new(format!("line {}: expected `name = effect`", number + 1)));
+            }
+            let effect = effect.parse().map_err(|e| ParseEffectError::new(format!("line {}: {}", number + 1, e)))?;
+            library.insert(name, effect);
+        }
+        Ok(library)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_render() {
+    let ramp = HapticEffect::Ramp { from: 0.0, to: 1.0, duration: 0.025 };
+    assert_eq!(ramp.render(320), [0.0, 0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875]);
+
+    let click = HapticEffect::Click { amplitude: 1.0 };
+    let samples = click.render(320);
+    assert_eq!(samples.len(), 8);
+    assert_eq!(samples[0], 1.0);
+    assert!(samples.windows(2).all(|w| w[1] < w[0]));
+
+    let heartbeat = HapticEffect::Heartbeat { amplitude: 1.0, bpm: 60.0, beats: 2 };
+    let samples = heartbeat.render(100);
+    assert_eq!(samples.len(), 200);
+    assert_eq!(&samples[0..8], &[1.0; 8]);
+    assert_eq!(samples[10], 0.0);
+    assert_eq!(samples[20], 0.6);
+    assert_eq!(samples[100], 1.0);
+
+    let tone = HapticEffect::Tone {
+        amplitude: 1.0,
+        frequency: 0.0,
+        duration: 1.0,
+        envelope: Envelope { attack: 0.5, decay: 0.0, sustain: 1.0, release: 0.25 },
+    };
+    let samples = tone.render(4);
+    assert_eq!(samples, [0.0, 0.5, 1.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_render_for() {
+    let desc = ovrTouchHapticsDesc {
+        _align: [],
+        SampleRateHz: 320,
+        SampleSizeInBytes: 1,
+        QueueMinSizeToAvoidStarvation: 4,
+        SubmitMinSamples: 1,
+        SubmitMaxSamples: 256,
+        SubmitOptimalSamples: 20,
+    };
+    assert_eq!(HapticEffect::Tick { amplitude: 1.0 }.render_for(&desc).unwrap(), [255, 255, 255, 255]);
+    let missing: ovrTouchHapticsDesc = unsafe { ::std::mem::zeroed() };
+    assert_eq!(HapticEffect::Tick { amplitude: 1.0 }.render_for(&missing).unwrap_err().result(), ovrError_DeviceUnavailable);
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse() {
+    let effects = [
+        HapticEffect::Click { amplitude: 0.75 },
+        HapticEffect::Tick { amplitude: 0.5 },
+        HapticEffect::Buzz { amplitude: 1.0, duration: 0.3 },
+        HapticEffect::Heartbeat { amplitude: 0.9, bpm: 72.0, beats: 3 },
+        HapticEffect::Ramp { from: 1.0, to: 0.0, duration: 0.5 },
+        HapticEffect::Tone {
+            amplitude: 0.6,
+            frequency: 20.0,
+            duration: 2.0,
+            envelope: Envelope { attack: 0.1, decay: 0.2, sustain: 0.5, release: 0.3 },
+        },
+    ];
+    for effect in effects.iter() {
+        assert_eq!(effect.to_string().parse::<HapticEffect>().unwrap(), *effect);
+    }
+    assert_eq!("click".parse::<HapticEffect>().unwrap(), HapticEffect::Click { amplitude: 1.0 });
+    assert!("click volume=1".parse::<HapticEffect>().is_err());
+    assert!("buzz duration=long".parse::<HapticEffect>().is_err());
+    assert!("rumble".parse::<HapticEffect>().is_err());
+    assert!("buzz duration=inf".parse::<HapticEffect>().is_err());
+    assert!("buzz duration=1e9".parse::<HapticEffect>().is_err());
+    assert!("ramp duration=-1".parse::<HapticEffect>().is_err());
+    assert!("tone amplitude=NaN".parse::<HapticEffect>().is_err());
+    assert!("heartbeat bpm=0.0001 beats=4000000000".parse::<HapticEffect>().is_err());
+    assert_eq!(HapticEffect::Buzz { amplitude: 1.0, duration: f32::INFINITY }.render(320).len(), 19200);
+
+    let library: EffectLibrary = "# comment\n\nhit = click amplitude=0.5\nalarm = buzz\n".parse().unwrap();
+    assert_eq!(library.get("hit"), Some(&HapticEffect::Click { amplitude: 0.5 }));
+    assert_eq!(library.to_string().parse::<EffectLibrary>().unwrap(), library);
+    assert!("not an effect".parse::<EffectLibrary>().is_err());
+}
@@ -0,0 +1,109 @@
+use ::std::f64::consts::PI;
+
+use ::wav::AudioChannel;
+use super::encode_samples;
+
+/// Sample rate of the Touch haptics engine in Hz, as reported by `ovrTouchHapticsDesc::SampleRateHz`.
+pub const TOUCH_HAPTICS_SAMPLE_RATE: u32 = 320;
+/// Size of a Touch haptics sample in bytes, as reported by `ovrTouchHapticsDesc::SampleSizeInBytes`.
+pub const TOUCH_HAPTICS_SAMPLE_SIZE: usize = 1;
+
+/// Modes used to generate haptics amplitudes from audio.
+///
+/// Each haptics sample covers a window of audio samples, the audio sample period multiplied by the
+/// ratio of the two sample rates. Audio is rectified (its absolute value is taken) before conversion,
+/// since haptics amplitudes cannot be negative.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HapticsGenMode {
+    /// Takes the audio sample at the start of each window, like `ovrHapticsGenMode_PointSample`.
+    PointSample,
+    /// Follows the RMS level of each window, which tracks perceived loudness.
+    Rms,
+    /// Takes the loudest sample in each window, so short transients are not lost.
+    Peak,
+    /// Low-pass filters the rectified audio at the haptics Nyquist frequency before point sampling,
+    /// giving a smooth envelope without aliasing.
+    LowPass,
+}
+
+/// Converts audio into haptics amplitudes in `[0.0, 1.0]` at `sample_rate` Hz.
+///
+/// This is a portable alternative to `ovr_GenHapticsFromAudioData` with additional generation modes.
+pub fn haptics_from_audio(audio: &AudioChannel, mode: HapticsGenMode, sample_rate: u32) -> Vec<f32> {
+    if audio.samples.is_empty() || audio.frequency == 0 || sample_rate == 0 {
+        return Vec::new();
+    }
+    let ratio = audio.frequency as f64 / sample_rate as f64;
+    let count = (audio.samples.len() as f64 / ratio).ceil() as usize;
+    let len = audio.samples.len();
+    let window = |i: usize| {
+        let start = ((i as f64 * ratio) as usize).min(len - 1);
+        let end = (((i + 1) as f64 * ratio) as usize).max(start + 1).min(len);
+        &audio.samples[start..end]
+    };
+    let amplitudes: Vec<f32> = match mode {
+        HapticsGenMode::PointSample => (0..count).map(|i| window(i)[0].abs()).collect(),
+        HapticsGenMode::Rms => (0..count).map(|i| {
+            let samples = window(i);
+            let sum: f64 = samples.iter().map(|&s| s as f64 * s as f64).sum();
+            (sum / samples.len() as f64).sqrt() as f32
+        }).collect(),
+        HapticsGenMode::Peak => (0..count).map(|i| window(i).iter().fold(0.0f32, |peak, &s| peak.max(s.abs()))).collect(),
+        HapticsGenMode::LowPass => {
+            let cutoff = sample_rate as f64 / 2.0;
+            let alpha = (1.0 - (-2.0 * PI * cutoff / audio.frequency as f64).exp()) as f32;
+            let mut level = 0.0f32;
+            let filtered: Vec<f32> = audio.samples.iter().map(|&s| {
+                level += alpha * (s.abs() - level);
+                level
+            }).collect();
+            (0..count).map(|i| filtered[((i as f64 * ratio) as usize).min(len - 1)]).collect()
+        }
+    };
+    amplitudes.into_iter().map(|a| a.min(1.0)).collect()
+}
+
+/// Converts audio into Touch haptics data, in the format used by `ovrHapticsClip` and `ovrHapticsBuffer`.
+///
+/// The result holds one byte per sample at `TOUCH_HAPTICS_SAMPLE_RATE`.
+///
+/// ```
+/// # use ovr_sys::wav::AudioChannel;
+/// # use ovr_sys::haptics::{haptics_clip_from_audio, HapticsGenMode};
+/// let audio = AudioChannel { samples: vec![0.5; 44100], frequency: 44100 };
+/// let clip = haptics_clip_from_audio(&audio, HapticsGenMode::Rms);
+/// assert_eq!(clip.len(), 320);
+/// assert!(clip.iter().all(|&s| s == 128));
+/// ```
+pub fn haptics_clip_from_audio(audio: &AudioChannel, mode: HapticsGenMode) -> Vec<u8> {
+    let amplitudes = haptics_from_audio(audio, mode, TOUCH_HAPTICS_SAMPLE_RATE);
+    let mut out = Vec::with_capacity(amplitudes.len() * TOUCH_HAPTICS_SAMPLE_SIZE);
+    encode_samples(&amplitudes, TOUCH_HAPTICS_SAMPLE_SIZE, &mut out);
+    out
+}
+
+#[cfg(test)]
+#[test]
+fn test_haptics_from_audio() {
+    let audio = AudioChannel {
+        samples: vec![0.5, -1.0, 0.0, 0.0, -0.25, 0.25, 0.0, 0.0, 0.0, 0.0],
+        frequency: 4,
+    };
+    assert_eq!(haptics_from_audio(&audio, HapticsGenMode::PointSample, 2), [0.5, 0.0, 0.25, 0.0, 0.0]);
+    assert_eq!(haptics_from_audio(&audio, HapticsGenMode::Peak, 2), [1.0, 0.0, 0.25, 0.0, 0.0]);
+    let rms = haptics_from_audio(&audio, HapticsGenMode::Rms, 2);
+    assert!((rms[0] - 0.625f32.sqrt()).abs() < 1e-6);
+    assert!((rms[2] - 0.25).abs() < 1e-6);
+
+    // A constant signal settles to its own level once the filter has caught up.
+    let constant = AudioChannel { samples: vec![-0.5; 1000], frequency: 1000 };
+    let low_pass = haptics_from_audio(&constant, HapticsGenMode::LowPass, 100);
+    assert_eq!(low_pass.len(), 100);
+    assert!(low_pass[0] < 0.5);
+    assert!((low_pass[99] - 0.5).abs() < 1e-4);
+
+    // Upsampling repeats samples rather than inventing new ones.
+    let short = AudioChannel { samples: vec![1.0, 0.5], frequency: 1 };
+    assert_eq!(haptics_from_audio(&short, HapticsGenMode::PointSample, 2), [1.0, 1.0, 0.5, 0.5]);
+    assert!(haptics_from_audio(&AudioChannel { samples: vec![], frequency: 1 }, HapticsGenMode::Rms, 2).is_empty());
+}
@@ -0,0 +1,104 @@
+use ::{
+    ovrControllerType,
+    ovrControllerType_LTouch,
+    ovrControllerType_RTouch,
+    ovrTouchHapticsDesc
+};
+
+mod effect;
+mod generate;
+mod player;
+
+pub use self::effect::{
+    CLICK_DURATION,
+    MAX_EFFECT_DURATION,
+    TICK_DURATION,
+    EffectLibrary,
+    Envelope,
+    HapticEffect,
+    ParseEffectError
+};
+pub use self::generate::{
+    TOUCH_HAPTICS_SAMPLE_RATE,
+    TOUCH_HAPTICS_SAMPLE_SIZE,
+    haptics_clip_from_audio,
+    haptics_from_audio,
+    HapticsGenMode
+};
+pub use self::player::{
+    BlendMode,
+    HapticsPlayer
+};
+
+/// The Touch controllers that support buffered haptics.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TouchController {
+    Left,
+    Right,
+}
+
+impl TouchController {
+    /// The corresponding `ovrControllerType`.
+    pub fn as_raw(&self) -> ovrControllerType {
+        match *self {
+            TouchController::Left => ovrControllerType_LTouch,
+            TouchController::Right => ovrControllerType_RTouch,
+        }
+    }
+}
+
+/// Whether a haptics description has a sample rate and sample size that samples can be encoded
+/// for. `ovr_GetTouchHapticsDesc` returns a zeroed description for controllers without one.
+fn has_haptics_engine(desc: &ovrTouchHapticsDesc) -> bool {
+    desc.SampleRateHz > 0 && (1..=4).contains(&desc.SampleSizeInBytes)
+}
+
+/// Converts amplitudes in the `[0.0, 1.0]` range into the opaque sample format expected by
+/// `ovrHapticsBuffer`, appending the encoded bytes to `out`.
+///
+/// Each sample is scaled to `[0, 2^(sample_size*8)-1]` and stored little-endian in `sample_size` bytes,
+/// as described by `ovrTouchHapticsDesc::SampleSizeInBytes`. Out of range amplitudes are clamped.
+///
+/// `sample_size` must be between 1 and 4.
+pub fn encode_samples(samples: &[f32], sample_size: usize, out: &mut Vec<u8>) {
+    assert!((1..=4).contains(&sample_size), "unsupported haptics sample size {}", sample_size);
+    let max = (((1u64 << (sample_size * 8)) - 1) as f64) as f32;
+    out.reserve(samples.len() * sample_size);
+    for &sample in samples {
+        let amplitude = if sample.is_nan() { 0.0 } else { sample.clamp(0.0, 1.0) };
+        let value = (amplitude * max).round() as u32;
+        for byte in 0..sample_size {
+            out.push((value >> (byte * 8)) as u8);
+        }
+    }
+}
+
+/// Converts samples in the opaque format of `ovrHapticsBuffer` back into amplitudes in `[0.0, 1.0]`.
+///
+/// This is the inverse of [`encode_samples`](fn.encode_samples.html). Trailing bytes that do not make
+/// up a whole sample are ignored.
+pub fn decode_samples(bytes: &[u8], sample_size: usize) -> Vec<f32> {
+    assert!((1..=4).contains(&sample_size), "unsupported haptics sample size {}", sample_size);
+    let max = ((1u64 << (sample_size * 8)) - 1) as f64;
+    bytes.chunks(sample_size)
+        .filter(|sample| sample.len() == sample_size)
+        .map(|sample| {
+            let value = sample.iter().rev().fold(0u64, |value, &byte| (value << 8) | byte as u64);
+            (value as f64 / max) as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn test_encode_samples() {
+    let mut out = Vec::new();
+    encode_samples(&[0.0, 0.5, 1.0, 2.0, -1.0], 1, &mut out);
+    assert_eq!(out, [0, 128, 255, 255, 0]);
+
+    out.clear();
+    encode_samples(&[1.0, 0.0], 2, &mut out);
+    assert_eq!(out, [0xff, 0xff, 0, 0]);
+    assert_eq!(decode_samples(&out, 2), [1.0, 0.0]);
+    assert_eq!(decode_samples(&[0, 255, 51, 7], 1), [0.0, 1.0, 0.2, 7.0 / 255.0]);
+}
@@ -0,0 +1,249 @@
+use ::std::collections::VecDeque;
+use ::std::mem;
+
+use ::libc::{
+    c_int,
+    c_void
+};
+
+use ::{
+    ovrControllerType,
+    ovrError_DeviceUnavailable,
+    ovrHapticsBuffer,
+    ovrHapticsBufferSubmit_Enqueue,
+    ovrHapticsPlaybackState,
+    ovrSuccess_DeviceUnavailable,
+    ovrTouchHapticsDesc,
+    ovr_GetControllerVibrationState,
+    ovr_GetTouchHapticsDesc,
+    ovr_SubmitControllerVibration
+};
+use ::error::{
+    check_call,
+    Error,
+    Result
+};
+use ::session::Session;
+use super::{
+    encode_samples,
+    has_haptics_engine,
+    HapticEffect,
+    TouchController,
+    MAX_EFFECT_DURATION
+};
+
+/// How samples passed to [`HapticsPlayer::blend`](struct.HapticsPlayer.html#method.blend) are combined
+/// with samples that are already waiting to be submitted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Keep the stronger of the two amplitudes.
+    Max,
+    /// Add the amplitudes, clamping the result to 1.0.
+    Add,
+}
+
+/// Streams buffered haptics to a single Touch controller through `ovr_SubmitControllerVibration`.
+///
+/// Samples are amplitudes in the `[0.0, 1.0]` range at the controller's `SampleRateHz`. They are held
+/// in a pending queue until [`update`](#method.update) moves them to the controller. `update` should be
+/// called at least once per frame; each call tops the controller's queue up to the optimal level from
+/// the `ovrTouchHapticsDesc`, so that it never drains below `QueueMinSizeToAvoidStarvation` while
+/// there is still pending data.
+///
+/// Note that buffered haptics cannot be mixed with `ovr_SetControllerVibration` on the same controller.
+///
+/// see [`ovr_SubmitControllerVibration`](../fn.ovr_SubmitControllerVibration.html), [`ovrTouchHapticsDesc`](../struct.ovrTouchHapticsDesc.html)
+pub struct HapticsPlayer {
+    session: Session,
+    controller: TouchController,
+    desc: ovrTouchHapticsDesc,
+    pending: VecDeque<f32>,
+    encoded: Vec<u8>,
+}
+
+impl HapticsPlayer {
+    /// Creates a player for `controller`, querying its haptics description from the runtime.
+    ///
+    /// Fails with `ovrError_DeviceUnavailable` if the runtime does not report a usable haptics engine
+    /// for the controller, for instance because it is not connected.
+    pub fn new(session: Session, controller: TouchController) -> Result<HapticsPlayer> {
+        let desc = unsafe { ovr_GetTouchHapticsDesc(session.as_raw(), controller.as_raw()) };
+        if !has_haptics_engine(&desc) {
+            return Err(Error::new(ovrError_DeviceUnavailable, format!("no haptics engine available for {:?}", controller)));
+        }
+        Ok(HapticsPlayer {
+            session,
+            controller,
+            desc,
+            pending: VecDeque::new(),
+            encoded: Vec::new(),
+        })
+    }
+
+    /// The controller this player submits to.
+    pub fn controller(&self) -> TouchController {
+        self.controller
+    }
+
+    /// The haptics description the player schedules against.
+    pub fn desc(&self) -> &ovrTouchHapticsDesc {
+        &self.desc
+    }
+
+    /// The sample rate of the controller in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.desc.SampleRateHz as u32
+    }
+
+    /// The number of samples that have not been submitted to the controller yet.
+    pub fn pending_samples(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Appends samples to the end of the pending queue, so that they play after everything already queued.
+    pub fn play(&mut self, samples: &[f32]) {
+        self.pending.extend(samples.iter().map(|&s| clamp_amplitude(s)));
+    }
+
+    /// Samples `curve` at the controller's sample rate for `duration` seconds and appends the result
+    /// to the pending queue.
+    ///
+    /// `curve` is given the time in seconds since the start of the curve and should return an amplitude.
+    /// Durations longer than [`MAX_EFFECT_DURATION`](constant.MAX_EFFECT_DURATION.html) are cut short.
+    pub fn play_curve<F: FnMut(f32) -> f32>(&mut self, duration: f32, mut curve: F) {
+        let rate = self.desc.SampleRateHz as f32;
+        let count = (duration.clamp(0.0, MAX_EFFECT_DURATION) * rate).ceil() as usize;
+        self.pending.extend((0..count).map(|i| clamp_amplitude(curve(i as f32 / rate))));
+    }
+
+    /// Renders `effect` at the controller's sample rate and appends it to the pending queue.
+    pub fn play_effect(&mut self, effect: &HapticEffect) {
+        let samples = effect.render(self.sample_rate());
+        self.play(&samples);
+    }
+
+    /// Mixes samples into the pending queue, starting with the next sample to be submitted.
+    ///
+    /// If `samples` is longer than the pending queue the remainder is appended.
+    pub fn blend(&mut self, samples: &[f32], mode: BlendMode) {
+        blend_into(&mut self.pending, samples, mode);
+    }
+
+    /// Discards all samples that have not been submitted yet.
+    ///
+    /// Samples already handed to the controller cannot be recalled and will finish playing. Since the
+    /// player keeps that queue short, they will last at most a few tens of milliseconds.
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Submits as many pending samples as are needed to keep the controller's queue at the optimal level.
+    ///
+    /// Returns the number of samples submitted. If the runtime reports the controller as unavailable
+    /// nothing is submitted and the pending samples are kept.
+    pub fn update(&mut self) -> Result<usize> {
+        let session = self.session.as_raw();
+        let controller: ovrControllerType = self.controller.as_raw();
+        let mut state: ovrHapticsPlaybackState = unsafe { mem::zeroed() };
+        let result = check_call("ovr_GetControllerVibrationState", || format!("{:?}", self.controller), unsafe { ovr_GetControllerVibrationState(session, controller, &mut state as *mut _) })?;
+        if result == ovrSuccess_DeviceUnavailable {
+            return Ok(0);
+        }
+        let count = submission_size(&self.desc, &state, self.pending.len());
+        if count == 0 {
+            return Ok(0);
+        }
+        let min = self.desc.SubmitMinSamples.max(1) as usize;
+        let mut chunk: Vec<f32> = self.pending.iter().cloned().take(count).collect();
+        // Pad the tail of the stream up to the minimum submission size with silence.
+        while chunk.len() < min {
+            chunk.push(0.0);
+        }
+        self.encoded.clear();
+        encode_samples(&chunk, self.desc.SampleSizeInBytes as usize, &mut self.encoded);
+        let buffer = ovrHapticsBuffer {
+            Samples: self.encoded.as_ptr() as *const c_void,
+            SamplesCount: chunk.len() as c_int,
+            SubmitMode: ovrHapticsBufferSubmit_Enqueue,
+        };
+        let result = check_call("ovr_SubmitControllerVibration", || format!("{:?}, {} samples", self.controller, buffer.SamplesCount), unsafe { ovr_SubmitControllerVibration(session, controller, &buffer as *const _) })?;
+        if result == ovrSuccess_DeviceUnavailable {
+            return Ok(0);
+        }
+        self.pending.drain(..count);
+        Ok(count)
+    }
+}
+
+fn clamp_amplitude(sample: f32) -> f32 {
+    if sample.is_nan() { 0.0 } else { sample.clamp(0.0, 1.0) }
+}
+
+fn blend_into(pending: &mut VecDeque<f32>, samples: &[f32], mode: BlendMode) {
+    for (i, &sample) in samples.iter().enumerate() {
+        let sample = clamp_amplitude(sample);
+        if let Some(existing) = pending.get_mut(i) {
+            *existing = match mode {
+                BlendMode::Max => existing.max(sample),
+                BlendMode::Add => (*existing + sample).min(1.0),
+            };
+        } else {
+            pending.push_back(sample);
+        }
+    }
+}
+
+/// Decides how many of `pending` samples to submit given the controller's current playback state.
+fn submission_size(desc: &ovrTouchHapticsDesc, state: &ovrHapticsPlaybackState, pending: usize) -> usize {
+    let queued = state.SamplesQueued.max(0) as usize;
+    let target = desc.SubmitOptimalSamples.max(desc.QueueMinSizeToAvoidStarvation).max(1) as usize;
+    if pending == 0 || queued >= target {
+        return 0;
+    }
+    let min = desc.SubmitMinSamples.max(1) as usize;
+    let max = (desc.SubmitMaxSamples.max(1) as usize).min(state.RemainingQueueSpace.max(0) as usize);
+    if max < min {
+        return 0;
+    }
+    (target - queued).max(min).min(max).min(pending)
+}
+
+#[cfg(test)]
+fn test_desc() -> ovrTouchHapticsDesc {
+    ovrTouchHapticsDesc {
+        _align: [],
+        SampleRateHz: 320,
+        SampleSizeInBytes: 1,
+        QueueMinSizeToAvoidStarvation: 4,
+        SubmitMinSamples: 1,
+        SubmitMaxSamples: 256,
+        SubmitOptimalSamples: 20,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_submission_size() {
+    let desc = test_desc();
+    let state = |queued, space| ovrHapticsPlaybackState { RemainingQueueSpace: space, SamplesQueued: queued };
+    // Empty queue is topped up to the optimal level.
+    assert_eq!(submission_size(&desc, &state(0, 256), 1000), 20);
+    // Partially drained queue only receives the difference.
+    assert_eq!(submission_size(&desc, &state(15, 241), 1000), 5);
+    // A full enough queue receives nothing.
+    assert_eq!(submission_size(&desc, &state(20, 236), 1000), 0);
+    // Limited by the remaining space and by what is pending.
+    assert_eq!(submission_size(&desc, &state(0, 8), 1000), 8);
+    assert_eq!(submission_size(&desc, &state(0, 256), 3), 3);
+    assert_eq!(submission_size(&desc, &state(0, 256), 0), 0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_blend() {
+    let mut pending: VecDeque<f32> = vec![0.5, 0.5].into_iter().collect();
+    blend_into(&mut pending, &[0.25, 0.75, 1.0], BlendMode::Max);
+    assert_eq!(pending.iter().cloned().collect::<Vec<_>>(), [0.5, 0.75, 1.0]);
+    blend_into(&mut pending, &[0.75], BlendMode::Add);
+    assert_eq!(pending.iter().cloned().collect::<Vec<_>>(), [1.0, 0.75, 1.0]);
+}
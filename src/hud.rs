@@ -0,0 +1,366 @@
+use ::libc::c_int;
+
+use ::{
+    ovrColorf,
+    ovrDebugHudStereoMode,
+    ovrDebugHudStereo_CrosshairAtInfinity,
+    ovrDebugHudStereo_Off,
+    ovrDebugHudStereo_Quad,
+    ovrDebugHudStereo_QuadWithCrosshair,
+    ovrLayerHudMode,
+    ovrLayerHud_Info,
+    ovrLayerHud_Off,
+    ovrPerfHudMode,
+    ovrPerfHud_AppRenderTiming,
+    ovrPerfHud_CompRenderTiming,
+    ovrPerfHud_LatencyTiming,
+    ovrPerfHud_Off,
+    ovrPerfHud_PerfSummary,
+    ovrPerfHud_VersionInfo,
+    ovrVector2f,
+    ovrVector3f
+};
+use ::error::Result;
+use ::properties::{
+    LayerHud,
+    LayerHudCurrentLayer,
+    LayerHudShowAllLayers,
+    PerfHud,
+    StereoGuideColor,
+    StereoGuideInfoEnable,
+    StereoGuidePosition,
+    StereoGuideSize,
+    StereoGuideYawPitchRoll,
+    StereoHud
+};
+use ::session::Session;
+
+/// The page shown by the Performance HUD.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PerfHudMode {
+    /// `ovrPerfHud_Off`.
+    Off,
+    /// Performance summary and headroom, `ovrPerfHud_PerfSummary`.
+    PerfSummary,
+    /// Latency related timing, `ovrPerfHud_LatencyTiming`.
+    LatencyTiming,
+    /// Application render timing, `ovrPerfHud_AppRenderTiming`.
+    AppRenderTiming,
+    /// Compositor render timing, `ovrPerfHud_CompRenderTiming`.
+    CompRenderTiming,
+    /// SDK and HMD versions, `ovrPerfHud_VersionInfo`.
+    VersionInfo,
+}
+
+impl PerfHudMode {
+    /// Every mode, in the order they are cycled through.
+    pub const ALL: [PerfHudMode; 6] = [
+        PerfHudMode::Off,
+        PerfHudMode::PerfSummary,
+        PerfHudMode::LatencyTiming,
+        PerfHudMode::AppRenderTiming,
+        PerfHudMode::CompRenderTiming,
+        PerfHudMode::VersionInfo,
+    ];
+
+    /// The corresponding `ovrPerfHudMode`.
+    pub fn as_raw(&self) -> ovrPerfHudMode {
+        match *self {
+            PerfHudMode::Off => ovrPerfHud_Off,
+            PerfHudMode::PerfSummary => ovrPerfHud_PerfSummary,
+            PerfHudMode::LatencyTiming => ovrPerfHud_LatencyTiming,
+            PerfHudMode::AppRenderTiming => ovrPerfHud_AppRenderTiming,
+            PerfHudMode::CompRenderTiming => ovrPerfHud_CompRenderTiming,
+            PerfHudMode::VersionInfo => ovrPerfHud_VersionInfo,
+        }
+    }
+
+    /// The mode for an `ovrPerfHudMode`, or `None` for unknown values.
+    pub fn from_raw(raw: ovrPerfHudMode) -> Option<PerfHudMode> {
+        PerfHudMode::ALL.iter().cloned().find(|mode| mode.as_raw() == raw)
+    }
+
+    /// The mode after this one, wrapping around to `Off`.
+    pub fn next(&self) -> PerfHudMode {
+        next(&PerfHudMode::ALL, *self)
+    }
+}
+
+/// What the Layer HUD shows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LayerHudMode {
+    /// `ovrLayerHud_Off`.
+    Off,
+    /// Information about the layer selected by
+    /// [`DebugHudState::current_layer`](struct.DebugHudState.html#structfield.current_layer),
+    /// `ovrLayerHud_Info`.
+    Info,
+}
+
+impl LayerHudMode {
+    /// Every mode, in the order they are cycled through.
+    pub const ALL: [LayerHudMode; 2] = [LayerHudMode::Off, LayerHudMode::Info];
+
+    /// The corresponding `ovrLayerHudMode`.
+    pub fn as_raw(&self) -> ovrLayerHudMode {
+        match *self {
+            LayerHudMode::Off => ovrLayerHud_Off,
+            LayerHudMode::Info => ovrLayerHud_Info,
+        }
+    }
+
+    /// The mode for an `ovrLayerHudMode`, or `None` for unknown values.
+    pub fn from_raw(raw: ovrLayerHudMode) -> Option<LayerHudMode> {
+        LayerHudMode::ALL.iter().cloned().find(|mode| mode.as_raw() == raw)
+    }
+
+    /// The mode after this one, wrapping around to `Off`.
+    pub fn next(&self) -> LayerHudMode {
+        next(&LayerHudMode::ALL, *self)
+    }
+}
+
+/// The guide rendered by the Debug Stereo HUD.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StereoHudMode {
+    /// `ovrDebugHudStereo_Off`.
+    Off,
+    /// A quad in the world, `ovrDebugHudStereo_Quad`.
+    Quad,
+    /// A quad with a crosshair in the world, `ovrDebugHudStereo_QuadWithCrosshair`.
+    QuadWithCrosshair,
+    /// A screen-space crosshair at infinity, `ovrDebugHudStereo_CrosshairAtInfinity`.
+    CrosshairAtInfinity,
+}
+
+impl StereoHudMode {
+    /// Every mode, in the order they are cycled through.
+    pub const ALL: [StereoHudMode; 4] = [
+        StereoHudMode::Off,
+        StereoHudMode::Quad,
+        StereoHudMode::QuadWithCrosshair,
+        StereoHudMode::CrosshairAtInfinity,
+    ];
+
+    /// The corresponding `ovrDebugHudStereoMode`.
+    pub fn as_raw(&self) -> ovrDebugHudStereoMode {
+        match *self {
+            StereoHudMode::Off => ovrDebugHudStereo_Off,
+            StereoHudMode::Quad => ovrDebugHudStereo_Quad,
+            StereoHudMode::QuadWithCrosshair => ovrDebugHudStereo_QuadWithCrosshair,
+            StereoHudMode::CrosshairAtInfinity => ovrDebugHudStereo_CrosshairAtInfinity,
+        }
+    }
+
+    /// The mode for an `ovrDebugHudStereoMode`, or `None` for unknown values.
+    pub fn from_raw(raw: ovrDebugHudStereoMode) -> Option<StereoHudMode> {
+        StereoHudMode::ALL.iter().cloned().find(|mode| mode.as_raw() == raw)
+    }
+
+    /// The mode after this one, wrapping around to `Off`.
+    pub fn next(&self) -> StereoHudMode {
+        next(&StereoHudMode::ALL, *self)
+    }
+}
+
+fn next<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let index = all.iter().position(|mode| *mode == current).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+/// The placement and look of the quad and crosshair drawn by the Debug Stereo HUD, set through the
+/// `OVR_DEBUG_HUD_STEREO_GUIDE_*` keys.
+#[derive(Debug, Copy, Clone)]
+pub struct StereoGuide {
+    /// Whether to show information about the guide next to it.
+    pub info_enable: bool,
+    /// Width and height of the quad in meters.
+    pub size: ovrVector2f,
+    /// Position of the guide relative to the HMD in meters.
+    pub position: ovrVector3f,
+    /// Yaw, pitch and roll of the guide in radians.
+    pub yaw_pitch_roll: ovrVector3f,
+    /// Colour of the guide.
+    pub color: ovrColorf,
+}
+
+/// The complete state of the Performance, Layer and Debug Stereo HUDs.
+#[derive(Debug, Copy, Clone)]
+pub struct DebugHudState {
+    /// The Performance HUD page.
+    pub perf: PerfHudMode,
+    /// The Layer HUD mode.
+    pub layer: LayerHudMode,
+    /// The index of the layer described by the Layer HUD.
+    pub current_layer: c_int,
+    /// Whether the Layer HUD leaves the other layers visible.
+    pub show_all_layers: bool,
+    /// The Debug Stereo HUD mode.
+    pub stereo: StereoHudMode,
+    /// The Debug Stereo HUD guide.
+    pub guide: StereoGuide,
+}
+
+/// Control of the Performance, Layer and Debug Stereo HUDs of a session.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::hud::{PerfHudMode, StereoHudMode};
+/// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+/// let hud = session.debug_hud();
+/// // Bound to a debug hotkey.
+/// let mode = hud.cycle_perf_hud()?;
+/// println!("performance HUD: {:?}", mode);
+///
+/// // A 20cm quad one meter ahead, in red.
+/// let mut state = hud.state();
+/// state.stereo = StereoHudMode::QuadWithCrosshair;
+/// state.guide.size = ovr_sys::ovrVector2f { _align: [], x: 0.2, y: 0.2 };
+/// state.guide.position = ovr_sys::ovrVector3f { _align: [], x: 0.0, y: 0.0, z: -1.0 };
+/// state.guide.color = ovr_sys::ovrColorf { _align: [], r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+/// hud.apply(&state)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct DebugHud {
+    session: Session,
+}
+
+impl Session {
+    /// Returns the debug HUD controls for this session.
+    pub fn debug_hud(&self) -> DebugHud {
+        DebugHud {
+            session: *self,
+        }
+    }
+}
+
+impl DebugHud {
+    /// The session whose HUDs are controlled.
+    pub fn session(&self) -> Session {
+        self.session
+    }
+
+    /// Reads back the state of all the HUDs.
+    ///
+    /// Modes the runtime reports that are not known to this crate are read as `Off`.
+    pub fn state(&self) -> DebugHudState {
+        let session = self.session;
+        let [width, height] = session.get(StereoGuideSize);
+        let [r, g, b, a] = session.get(StereoGuideColor);
+        DebugHudState {
+            perf: session.get(PerfHud),
+            layer: session.get(LayerHud),
+            current_layer: session.get(LayerHudCurrentLayer),
+            show_all_layers: session.get(LayerHudShowAllLayers),
+            stereo: session.get(StereoHud),
+            guide: StereoGuide {
+                info_enable: session.get(StereoGuideInfoEnable),
+                size: ovrVector2f { _align: [], x: width, y: height },
+                position: vector3(session.get(StereoGuidePosition)),
+                yaw_pitch_roll: vector3(session.get(StereoGuideYawPitchRoll)),
+                color: ovrColorf { _align: [], r, g, b, a },
+            },
+        }
+    }
+
+    /// Applies the state of all the HUDs, returning the state read back afterwards.
+    ///
+    /// If the runtime refuses any of the properties, those already set are restored to their
+    /// previous values and an `ovrError_InvalidOperation` error naming the property is returned.
+    pub fn apply(&self, state: &DebugHudState) -> Result<DebugHudState> {
+        let previous = self.state();
+        if let Err(err) = self.write(state) {
+            // Best effort, the property that failed is likely to fail again.
+            let _ = self.write(&previous);
+            return Err(err);
+        }
+        Ok(self.state())
+    }
+
+    /// The Performance HUD page.
+    pub fn perf_hud(&self) -> PerfHudMode {
+        self.session.get(PerfHud)
+    }
+
+    /// Shows a Performance HUD page.
+    pub fn set_perf_hud(&self, mode: PerfHudMode) -> Result<()> {
+        self.session.set(PerfHud, mode)
+    }
+
+    /// Switches the Performance HUD to its next page, returning the page shown.
+    pub fn cycle_perf_hud(&self) -> Result<PerfHudMode> {
+        let mode = self.perf_hud().next();
+        self.set_perf_hud(mode)?;
+        Ok(mode)
+    }
+
+    /// The Layer HUD mode.
+    pub fn layer_hud(&self) -> LayerHudMode {
+        self.session.get(LayerHud)
+    }
+
+    /// Sets the Layer HUD mode.
+    pub fn set_layer_hud(&self, mode: LayerHudMode) -> Result<()> {
+        self.session.set(LayerHud, mode)
+    }
+
+    /// Switches the Layer HUD to its next mode, returning the mode set.
+    pub fn cycle_layer_hud(&self) -> Result<LayerHudMode> {
+        let mode = self.layer_hud().next();
+        self.set_layer_hud(mode)?;
+        Ok(mode)
+    }
+
+    /// The Debug Stereo HUD mode.
+    pub fn stereo_hud(&self) -> StereoHudMode {
+        self.session.get(StereoHud)
+    }
+
+    /// Sets the Debug Stereo HUD mode.
+    pub fn set_stereo_hud(&self, mode: StereoHudMode) -> Result<()> {
+        self.session.set(StereoHud, mode)
+    }
+
+    /// Switches the Debug Stereo HUD to its next mode, returning the mode set.
+    pub fn cycle_stereo_hud(&self) -> Result<StereoHudMode> {
+        let mode = self.stereo_hud().next();
+        self.set_stereo_hud(mode)?;
+        Ok(mode)
+    }
+
+    fn write(&self, state: &DebugHudState) -> Result<()> {
+        let (session, guide) = (self.session, &state.guide);
+        session.set(PerfHud, state.perf)?;
+        session.set(LayerHudCurrentLayer, state.current_layer)?;
+        session.set(LayerHudShowAllLayers, state.show_all_layers)?;
+        session.set(LayerHud, state.layer)?;
+        session.set(StereoGuideInfoEnable, guide.info_enable)?;
+        session.set(StereoGuideSize, [guide.size.x, guide.size.y])?;
+        session.set(StereoGuidePosition, [guide.position.x, guide.position.y, guide.position.z])?;
+        session.set(StereoGuideYawPitchRoll, [guide.yaw_pitch_roll.x, guide.yaw_pitch_roll.y, guide.yaw_pitch_roll.z])?;
+        session.set(StereoGuideColor, [guide.color.r, guide.color.g, guide.color.b, guide.color.a])?;
+        session.set(StereoHud, state.stereo)
+    }
+}
+
+fn vector3([x, y, z]: [f32; 3]) -> ovrVector3f {
+    ovrVector3f { _align: [], x, y, z }
+}
+
+#[cfg(test)]
+#[test]
+fn test_hud_modes() {
+    for &mode in &PerfHudMode::ALL {
+        assert_eq!(PerfHudMode::from_raw(mode.as_raw()), Some(mode));
+    }
+    for &mode in &StereoHudMode::ALL {
+        assert_eq!(StereoHudMode::from_raw(mode.as_raw()), Some(mode));
+    }
+    assert_eq!(PerfHudMode::from_raw(::ovrPerfHud_Count), None);
+    assert_eq!(PerfHudMode::VersionInfo.next(), PerfHudMode::Off);
+    assert_eq!(LayerHudMode::Off.next(), LayerHudMode::Info);
+    assert_eq!(LayerHudMode::Info.next(), LayerHudMode::Off);
+    assert_eq!(StereoHudMode::Quad.next(), StereoHudMode::QuadWithCrosshair);
+}
@@ -0,0 +1,115 @@
+use ::std::fmt::{
+    Display,
+    Write
+};
+
+/// Appends `s` as a JSON string literal.
+pub fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Formats a number, writing non-finite values as `null` since JSON cannot represent them.
+pub fn number<T: Into<f64> + Display + Copy>(value: T) -> String {
+    if value.into().is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Writes a JSON object one member at a time.
+pub struct ObjectWriter<'a> {
+    out: &'a mut String,
+    empty: bool,
+}
+
+impl<'a> ObjectWriter<'a> {
+    pub fn new(out: &'a mut String) -> ObjectWriter<'a> {
+        out.push('{');
+        ObjectWriter {
+            out,
+            empty: true,
+        }
+    }
+
+    /// Starts a member, returning the output to write its value to.
+    pub fn key(&mut self, name: &str) -> &mut String {
+        if !self.empty {
+            self.out.push(',');
+        }
+        self.empty = false;
+        write_string(self.out, name);
+        self.out.push(':');
+        self.out
+    }
+
+    /// Writes a member with a value that is already formatted as JSON.
+    pub fn raw(&mut self, name: &str, value: &str) {
+        self.key(name).push_str(value);
+    }
+
+    pub fn finish(self) {
+        self.out.push('}');
+    }
+}
+
+/// Writes a JSON array one item at a time.
+pub struct ArrayWriter<'a> {
+    out: &'a mut String,
+    empty: bool,
+}
+
+impl<'a> ArrayWriter<'a> {
+    pub fn new(out: &'a mut String) -> ArrayWriter<'a> {
+        out.push('[');
+        ArrayWriter {
+            out,
+            empty: true,
+        }
+    }
+
+    /// Starts an item, returning the output to write it to.
+    pub fn item(&mut self) -> &mut String {
+        if !self.empty {
+            self.out.push(',');
+        }
+        self.empty = false;
+        self.out
+    }
+
+    pub fn finish(self) {
+        self.out.push(']');
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_writers() {
+    let mut out = String::new();
+    {
+        let mut object = ObjectWriter::new(&mut out);
+        write_string(object.key("name"), "a \"quoted\"\n\u{1}");
+        object.raw("value", &number(1.5f32));
+        object.raw("missing", &number(f32::NAN));
+        let mut array = ArrayWriter::new(object.key("list"));
+        array.item().push_str(&number(1));
+        array.item().push_str("{}");
+        array.finish();
+        object.finish();
+    }
+    assert_eq!(out, r#"{"name":"a \"quoted\"\n\u0001","value":1.5,"missing":null,"list":[1,{}]}"#);
+}
@@ -41,18 +41,32 @@
 //!
 //! Optional features are provided in sub-modules. These features are `audio`, `directx`, `opengl` and `vulkan`.
 //! These sub-modules will only be present if the corresponding feature has been enabled in the
-//! Cargo manifest. `opengl` is enabled by default.
+//! Cargo manifest. `opengl` is enabled by default. The `log` and `tracing` features enable
+//! forwarding of LibOVR log messages to those crates in the [`logging`](logging/index.html) module.
+//! The `trace` feature wraps every LibOVR function to report its calls to the sink set in the
+//! [`trace`](trace/index.html) module.
+//!
+//! Alongside the raw bindings, some safe helpers are provided for the parts of the API that are
+//! awkward to use directly. These take a [`session::Session`](session/struct.Session.html), a non-owning
+//! handle to an `ovrSession` created by the application, and report failures through
+//! [`error::Error`](error/struct.Error.html).
 
 #![allow(non_camel_case_types, non_upper_case_globals, non_snake_case)]
 
 extern crate libc;
 
-#[cfg(all(feature = "directx", windows))]
+#[cfg(all(any(feature = "directx", feature = "audio"), windows))]
 extern crate winapi;
 
 #[cfg(feature = "vulkan")]
 extern crate vks;
 
+#[cfg(feature = "log")]
+extern crate log;
+
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
 use libc::{
     c_char,
     c_int,
@@ -79,6 +93,47 @@ pub mod vulkan;
 #[cfg(all(feature = "audio", windows))]
 pub mod audio;
 
+/// Error type shared by the safe wrappers, and a history of the calls they made that failed or
+/// returned a qualified success. Calls made directly to the raw `ovr_*` functions are only
+/// recorded with the `trace` feature.
+pub mod error;
+/// Non-owning session handle that the safe wrappers hang off.
+pub mod session;
+/// Buffered Touch haptics streaming, parametric haptic effects and audio to haptics conversion.
+pub mod haptics;
+/// Pure-Rust WAV decoding, equivalent to `ovr_ReadWavFromBuffer` but available on all platforms.
+pub mod wav;
+/// Safe access to the Boundary (Guardian) system and its geometry.
+pub mod boundary;
+/// Typed tracked devices, safe pose queries, tracking sensors and their coverage, tracking origin management and persistent anchors.
+pub mod tracking;
+/// Performance stats collection, analysis and timeline export.
+pub mod perf;
+/// Typed control of the Performance, Layer and Debug Stereo HUDs.
+pub mod hud;
+/// Typed access to LibOVR properties such as the user profile keys.
+pub mod properties;
+/// The user profile and values derived from it, such as the neck model.
+pub mod profile;
+/// LibOVR log callbacks as Rust closures, and forwarding between LibOVR and the `log` and `tracing` crates.
+pub mod logging;
+/// Recording of tracking, input, session status and boundary queries to a file, and their deterministic replay without a headset.
+pub mod replay;
+/// Tracing of every LibOVR call, with its arguments, duration and result, to a pluggable sink.
+#[cfg(feature = "trace")]
+pub mod trace;
+// The generator of the `trace` wrappers run by the build script, included to test it.
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../build/trace.rs"]
+mod trace_build;
+
+mod json;
+mod math;
+
+#[cfg(feature = "trace")]
+include!(concat!(env!("OUT_DIR"), "/trace.rs"));
+
 pub const OVR_PRODUCT_VERSION: u32 = 1;
 pub const OVR_MAJOR_VERSION: u32 = 1;
 pub const OVR_MINOR_VERSION: u32 = 15;
@@ -634,6 +689,32 @@ pub struct ovrHmdDesc {
     /// \internal struct paddding.
     pub _pad1: [u8; 4],
 }
+impl fmt::Debug for ovrHmdDesc {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fn string(chars: &[c_char]) -> String {
+            let bytes = chars.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect::<Vec<_>>();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        fmt.debug_struct("ovrHmdDesc")
+            .field("Type", &self.Type)
+            .field("ProductName", &string(&self.ProductName))
+            .field("Manufacturer", &string(&self.Manufacturer))
+            .field("VendorId", &self.VendorId)
+            .field("ProductId", &self.ProductId)
+            .field("SerialNumber", &string(&self.SerialNumber))
+            .field("FirmwareMajor", &self.FirmwareMajor)
+            .field("FirmwareMinor", &self.FirmwareMinor)
+            .field("AvailableHmdCaps", &self.AvailableHmdCaps)
+            .field("DefaultHmdCaps", &self.DefaultHmdCaps)
+            .field("AvailableTrackingCaps", &self.AvailableTrackingCaps)
+            .field("DefaultTrackingCaps", &self.DefaultTrackingCaps)
+            .field("DefaultEyeFov", &self.DefaultEyeFov)
+            .field("MaxEyeFov", &self.MaxEyeFov)
+            .field("Resolution", &self.Resolution)
+            .field("DisplayRefreshRate", &self.DisplayRefreshRate)
+            .finish()
+    }
+}
 
 #[cfg(windows)]
 pub type ovrProcessId = u32;
@@ -1318,6 +1399,7 @@ pub struct ovrInitParams {
 
 }
 
+#[cfg(not(feature = "trace"))]
 extern "C" {
 
     // -----------------------------------------------------------------------------------
@@ -1596,6 +1678,7 @@ pub struct ovrSessionStatus {
     pub ShouldRecenter: ovrBool,
 }
 
+#[cfg(not(feature = "trace"))]
 extern "C" {
 
     /// Returns status information for the application.
@@ -2236,6 +2319,7 @@ pub struct ovrLayerQuad {
 //
 //@{
 
+#[cfg(not(feature = "trace"))]
 extern "C" {
 
     /// TextureSwapChain creation is rendering API-specific.
@@ -2646,6 +2730,7 @@ pub struct ovrPerfStats {
     pub VisibleProcessId: ovrProcessId,
 }
 
+#[cfg(not(feature = "trace"))]
 extern "C" {
 
     /// Retrieves performance stats for the VR app as well as the SDK compositor.
@@ -2823,6 +2908,7 @@ pub const ovrDebugHudStereo_Count: ovrDebugHudStereoMode = 4;
 // These functions read and write OVR properties. Supported properties
 // are defined in `OVR_CAPI_Keys`.h
 //
+#[cfg(not(feature = "trace"))]
 extern "C" {
 
     /// Reads a boolean property.
@@ -3107,6 +3193,7 @@ fn test_detect_result() {
     assert!(::std::mem::size_of::<ovrDetectResult>() == 8, "ovrDetectResult size mismatch");
 }
 
+#[cfg(not(feature = "trace"))]
 extern "C" {
     /// Detects Oculus Runtime and Device Status
     ///
@@ -0,0 +1,232 @@
+use ::std::ffi::{
+    CStr,
+    CString
+};
+use ::std::panic::{
+    self,
+    AssertUnwindSafe
+};
+
+use ::libc::{
+    c_char,
+    c_int
+};
+
+use ::{
+    ovrError_InvalidParameter,
+    ovrInitParams,
+    ovrLogLevel,
+    ovrLogLevel_Debug,
+    ovrLogLevel_Error,
+    ovrLogLevel_Info,
+    ovr_TraceMessage
+};
+use ::error::{
+    Error,
+    Result
+};
+
+/// The target used for LibOVR messages forwarded to the `log` and `tracing` crates.
+pub const LOG_TARGET: &str = "libovr";
+
+/// The severity of a LibOVR log message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// `ovrLogLevel_Debug`.
+    Debug,
+    /// `ovrLogLevel_Info`.
+    Info,
+    /// `ovrLogLevel_Error`.
+    Error,
+}
+
+impl LogLevel {
+    /// The corresponding `ovrLogLevel`.
+    pub fn as_raw(&self) -> ovrLogLevel {
+        match *self {
+            LogLevel::Debug => ovrLogLevel_Debug,
+            LogLevel::Info => ovrLogLevel_Info,
+            LogLevel::Error => ovrLogLevel_Error,
+        }
+    }
+
+    /// The level for an `ovrLogLevel`, or `None` for unknown values.
+    pub fn from_raw(raw: ovrLogLevel) -> Option<LogLevel> {
+        match raw {
+            ovrLogLevel_Debug => Some(LogLevel::Debug),
+            ovrLogLevel_Info => Some(LogLevel::Info),
+            ovrLogLevel_Error => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+type Callback = Box<dyn Fn(LogLevel, &str) + Send + Sync>;
+
+/// Installs a closure as the log callback of `ovr_Initialize`.
+///
+/// Sets `LogCallback` and `UserData` in `params`. Messages are converted from UTF-8 lossily, with
+/// trailing newlines removed, and levels the crate does not know are reported as `Info`. A panic
+/// in the closure is caught and the message dropped, since it cannot unwind into LibOVR.
+///
+/// LibOVR may call the closure from any thread until `ovr_Shutdown` returns, and has no way to say
+/// when it is done with `UserData`, so the closure is leaked. Install a callback once per
+/// initialization.
+///
+/// ```no_run
+/// # use ovr_sys::*;
+/// # use ovr_sys::logging::{set_log_callback, LogLevel};
+/// # unsafe {
+/// let mut params: ovrInitParams = ::std::mem::zeroed();
+/// set_log_callback(&mut params, |level, message| {
+///     if level == LogLevel::Error {
+///         eprintln!("LibOVR: {}", message);
+///     }
+/// });
+/// ovr_Initialize(&params as *const _);
+/// # }
+/// ```
+pub fn set_log_callback<F: Fn(LogLevel, &str) + Send + Sync + 'static>(params: &mut ovrInitParams, callback: F) {
+    let callback: Box<Callback> = Box::new(Box::new(callback));
+    params.LogCallback = Some(trampoline);
+    params.UserData = Box::into_raw(callback) as usize;
+}
+
+extern "C" fn trampoline(user_data: usize, level: c_int, message: *const c_char) {
+    if user_data == 0 || message.is_null() {
+        return;
+    }
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let callback = unsafe { &*(user_data as *const Callback) };
+        let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+        callback(LogLevel::from_raw(level).unwrap_or(LogLevel::Info), message.trim_end_matches(['\r', '\n']));
+    }));
+}
+
+/// Installs a log callback forwarding LibOVR messages to the `log` crate, with the target
+/// [`LOG_TARGET`](constant.LOG_TARGET.html).
+///
+/// Available with the `log` feature.
+#[cfg(feature = "log")]
+pub fn forward_to_log(params: &mut ovrInitParams) {
+    set_log_callback(params, |level, message| {
+        let level = match level {
+            LogLevel::Debug => ::log::Level::Debug,
+            LogLevel::Info => ::log::Level::Info,
+            LogLevel::Error => ::log::Level::Error,
+        };
+        ::log::log!(target: LOG_TARGET, level, "{}", message);
+    });
+}
+
+/// Installs a log callback forwarding LibOVR messages to the `tracing` crate as events with the
+/// target [`LOG_TARGET`](constant.LOG_TARGET.html).
+///
+/// Available with the `tracing` feature.
+#[cfg(feature = "tracing")]
+pub fn forward_to_tracing(params: &mut ovrInitParams) {
+    set_log_callback(params, |level, message| {
+        match level {
+            LogLevel::Debug => ::tracing::debug!(target: LOG_TARGET, "{}", message),
+            LogLevel::Info => ::tracing::info!(target: LOG_TARGET, "{}", message),
+            LogLevel::Error => ::tracing::error!(target: LOG_TARGET, "{}", message),
+        }
+    });
+}
+
+/// Writes a message to the LibOVR log with `ovr_TraceMessage`, so that it shows up in the Oculus
+/// service logs.
+///
+/// Nul characters are removed from the message. Fails with `ovrError_InvalidParameter` if LibOVR
+/// rejects the message as too large.
+pub fn trace_message(level: LogLevel, message: &str) -> Result<()> {
+    let message = CString::new(message.replace('\0', "")).expect("nul characters were removed");
+    if unsafe { ovr_TraceMessage(level.as_raw(), message.as_ptr()) } < 0 {
+        Err(Error::new(ovrError_InvalidParameter, "message is too large for ovr_TraceMessage"))
+    } else {
+        Ok(())
+    }
+}
+
+/// A `log` logger writing records to the LibOVR log through
+/// [`trace_message`](fn.trace_message.html).
+///
+/// Records with the [`LOG_TARGET`](constant.LOG_TARGET.html) target are skipped, so that messages
+/// forwarded from LibOVR by [`forward_to_log`](fn.forward_to_log.html) are not sent back. `Warn`
+/// records are written at the `Error` level and `Trace` records at the `Debug` level, since LibOVR
+/// has only three levels.
+///
+/// Available with the `log` feature.
+///
+/// ```no_run
+/// # use ovr_sys::logging::OvrLogger;
+/// OvrLogger::init(log::LevelFilter::Info).unwrap();
+/// log::warn!("asset cache is full");
+/// ```
+#[cfg(feature = "log")]
+#[derive(Debug, Copy, Clone)]
+pub struct OvrLogger {
+    level: ::log::LevelFilter,
+}
+
+#[cfg(feature = "log")]
+impl OvrLogger {
+    /// Creates a logger writing records at or above `level`.
+    pub fn new(level: ::log::LevelFilter) -> OvrLogger {
+        OvrLogger { level }
+    }
+
+    /// Installs a logger writing records at or above `level` as the global `log` logger.
+    pub fn init(level: ::log::LevelFilter) -> ::std::result::Result<(), ::log::SetLoggerError> {
+        ::log::set_boxed_logger(Box::new(OvrLogger::new(level)))?;
+        ::log::set_max_level(level);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "log")]
+impl ::log::Log for OvrLogger {
+    fn enabled(&self, metadata: &::log::Metadata) -> bool {
+        metadata.level() <= self.level && metadata.target() != LOG_TARGET
+    }
+
+    fn log(&self, record: &::log::Record) {
+        if self.enabled(record.metadata()) {
+            let level = match record.level() {
+                ::log::Level::Error | ::log::Level::Warn => LogLevel::Error,
+                ::log::Level::Info => LogLevel::Info,
+                ::log::Level::Debug | ::log::Level::Trace => LogLevel::Debug,
+            };
+            let _ = trace_message(level, &format!("{}: {}", record.target(), record.args()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+#[test]
+fn test_log_callback() {
+    use ::std::sync::{Arc, Mutex};
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mut params: ovrInitParams = unsafe { ::std::mem::zeroed() };
+    {
+        let received = received.clone();
+        set_log_callback(&mut params, move |level, message| {
+            if message == "boom" {
+                panic!("callback panicked");
+            }
+            received.lock().unwrap().push((level, message.to_string()));
+        });
+    }
+    let callback = params.LogCallback.unwrap();
+    callback(params.UserData, ovrLogLevel_Error, b"device lost\n\0".as_ptr() as *const c_char);
+    callback(params.UserData, 7, b"caf\xe9\0".as_ptr() as *const c_char);
+    callback(params.UserData, ovrLogLevel_Debug, b"boom\0".as_ptr() as *const c_char);
+    callback(params.UserData, ovrLogLevel_Debug, ::std::ptr::null());
+    assert_eq!(*received.lock().unwrap(), [
+        (LogLevel::Error, "device lost".to_string()),
+        (LogLevel::Info, "caf\u{fffd}".to_string()),
+    ]);
+}
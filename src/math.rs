@@ -0,0 +1,146 @@
+use ::{
+    ovrPosef,
+    ovrQuatf,
+    ovrVector2f,
+    ovrVector3f
+};
+
+/// Builds an `ovrVector2f`.
+pub fn vec2(x: f32, y: f32) -> ovrVector2f {
+    ovrVector2f {
+        _align: [],
+        x,
+        y,
+    }
+}
+
+/// Builds an `ovrVector3f`.
+pub fn vec3(x: f32, y: f32, z: f32) -> ovrVector3f {
+    ovrVector3f {
+        _align: [],
+        x,
+        y,
+        z,
+    }
+}
+
+/// Projects a point onto the floor plane, mapping X to X and Z to Y.
+pub fn floor(v: ovrVector3f) -> ovrVector2f {
+    vec2(v.x, v.z)
+}
+
+pub fn add2(a: ovrVector2f, b: ovrVector2f) -> ovrVector2f {
+    vec2(a.x + b.x, a.y + b.y)
+}
+
+pub fn sub2(a: ovrVector2f, b: ovrVector2f) -> ovrVector2f {
+    vec2(a.x - b.x, a.y - b.y)
+}
+
+pub fn scale2(a: ovrVector2f, s: f32) -> ovrVector2f {
+    vec2(a.x * s, a.y * s)
+}
+
+pub fn dot2(a: ovrVector2f, b: ovrVector2f) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+/// The Z component of the 3D cross product of `a` and `b`.
+pub fn cross2(a: ovrVector2f, b: ovrVector2f) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+pub fn length2(a: ovrVector2f) -> f32 {
+    dot2(a, a).sqrt()
+}
+
+/// Rotates `a` counter-clockwise by `angle` radians.
+pub fn rotate2(a: ovrVector2f, angle: f32) -> ovrVector2f {
+    let (sin, cos) = angle.sin_cos();
+    vec2(a.x * cos - a.y * sin, a.x * sin + a.y * cos)
+}
+
+/// Closest point to `p` on the segment from `a` to `b`.
+pub fn closest_on_segment(p: ovrVector2f, a: ovrVector2f, b: ovrVector2f) -> ovrVector2f {
+    let ab = sub2(b, a);
+    let len_sq = dot2(ab, ab);
+    if len_sq == 0.0 {
+        return a;
+    }
+    let t = (dot2(sub2(p, a), ab) / len_sq).clamp(0.0, 1.0);
+    add2(a, scale2(ab, t))
+}
+
+pub fn add3(a: ovrVector3f, b: ovrVector3f) -> ovrVector3f {
+    vec3(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+pub fn sub3(a: ovrVector3f, b: ovrVector3f) -> ovrVector3f {
+    vec3(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+pub fn scale3(a: ovrVector3f, s: f32) -> ovrVector3f {
+    vec3(a.x * s, a.y * s, a.z * s)
+}
+
+pub fn cross3(a: ovrVector3f, b: ovrVector3f) -> ovrVector3f {
+    vec3(a.y * b.z - a.z * b.y, a.z * b.x - a.x * b.z, a.x * b.y - a.y * b.x)
+}
+
+/// Rotates `v` by the unit quaternion `q`.
+pub fn rotate3(q: ovrQuatf, v: ovrVector3f) -> ovrVector3f {
+    let axis = vec3(q.x, q.y, q.z);
+    let t = scale3(cross3(axis, v), 2.0);
+    add3(add3(v, scale3(t, q.w)), cross3(axis, t))
+}
+
+/// The inverse of a unit quaternion.
+pub fn conjugate(q: ovrQuatf) -> ovrQuatf {
+    ovrQuatf {
+        _align: [],
+        x: -q.x,
+        y: -q.y,
+        z: -q.z,
+        w: q.w,
+    }
+}
+
+/// Transforms a point from the local space of `pose` into the space `pose` is expressed in.
+pub fn transform(pose: &ovrPosef, v: ovrVector3f) -> ovrVector3f {
+    add3(rotate3(pose.Orientation, v), pose.Position)
+}
+
+/// Transforms a point into the local space of `pose`.
+pub fn inverse_transform(pose: &ovrPosef, v: ovrVector3f) -> ovrVector3f {
+    rotate3(conjugate(pose.Orientation), sub3(v, pose.Position))
+}
+
+/// The Hamilton product `a * b`, which applies `b` and then `a`.
+pub fn quat_mul(a: ovrQuatf, b: ovrQuatf) -> ovrQuatf {
+    ovrQuatf {
+        _align: [],
+        x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+        y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+        z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+    }
+}
+
+/// Composes two poses, giving the pose of `b`'s local space expressed in the space `a` is expressed in.
+pub fn pose_mul(a: &ovrPosef, b: &ovrPosef) -> ovrPosef {
+    ovrPosef {
+        _align: [],
+        Orientation: quat_mul(a.Orientation, b.Orientation),
+        Position: transform(a, b.Position),
+    }
+}
+
+/// The inverse of a pose.
+pub fn pose_inverse(pose: &ovrPosef) -> ovrPosef {
+    let orientation = conjugate(pose.Orientation);
+    ovrPosef {
+        _align: [],
+        Orientation: orientation,
+        Position: rotate3(orientation, scale3(pose.Position, -1.0)),
+    }
+}
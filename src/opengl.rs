@@ -12,6 +12,10 @@ use ::libc::{
     c_uint
 };
 
+#[cfg(feature = "trace")]
+include!(concat!(env!("OUT_DIR"), "/trace_opengl.rs"));
+
+#[cfg(not(feature = "trace"))]
 extern "C" {
     /// Creates a TextureSwapChain suitable for use with OpenGL.
     ///
@@ -0,0 +1,290 @@
+use ::std::fmt;
+
+use ::{
+    ovrFalse,
+    ovrPerfStatsPerCompositorFrame
+};
+use super::{
+    counter_increase,
+    Percentiles,
+    PerfCollector
+};
+
+/// Thresholds used by a [`PerfAnalyzer`](struct.PerfAnalyzer.html).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnalyzerConfig {
+    /// Number of most recent frames to analyze.
+    pub window: usize,
+    /// Time available per frame in seconds, `1 / refresh rate`.
+    pub frame_budget: f32,
+    /// Consecutive frames with ASW active before it is reported as sustained.
+    pub asw_sustained_frames: usize,
+    /// Times ASW may switch on within the window before it is reported as toggling.
+    pub asw_toggle_threshold: u32,
+    /// Dropped frames within the window, app and compositor together, before a burst is reported.
+    pub drop_burst_threshold: u32,
+    /// Increase in seconds of the average `AppQueueAheadTime` between the first and second half of
+    /// the window before it is reported as growing.
+    pub queue_ahead_growth: f32,
+    /// Fraction of the available budget a median elapsed time must exceed to be reported as the
+    /// bottleneck.
+    pub bound_ratio: f32,
+    /// Smallest resolution scale to recommend.
+    pub min_resolution_scale: f32,
+    /// Largest resolution scale to recommend.
+    pub max_resolution_scale: f32,
+}
+
+impl Default for AnalyzerConfig {
+    fn default() -> AnalyzerConfig {
+        AnalyzerConfig {
+            window: 90,
+            frame_budget: 1.0 / 90.0,
+            asw_sustained_frames: 45,
+            asw_toggle_threshold: 3,
+            drop_burst_threshold: 3,
+            queue_ahead_growth: 0.002,
+            bound_ratio: 0.9,
+            min_resolution_scale: 0.5,
+            max_resolution_scale: 1.5,
+        }
+    }
+}
+
+/// A frame pacing problem found by a [`PerfAnalyzer`](struct.PerfAnalyzer.html).
+///
+/// Times are in seconds. The `Display` form is the [`explanation`](#method.explanation).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PerfAlert {
+    /// ASW has been active for the last `frames` frames, so the application is rendering at half
+    /// rate.
+    SustainedAsw { frames: usize },
+    /// ASW switched on `toggles` times within `frames` frames.
+    AswToggling { toggles: u32, frames: usize },
+    /// Frames were dropped by the application or the compositor within `frames` frames.
+    FrameDropBurst { app: u32, compositor: u32, frames: usize },
+    /// The average `AppQueueAheadTime` grew from `from` to `to` over the window.
+    QueueAheadGrowing { from: f32, to: f32 },
+    /// The median application GPU time leaves no room in the budget once the compositor's GPU time
+    /// is taken out.
+    GpuBound { app_gpu_time: f32, compositor_gpu_time: f32, budget: f32 },
+    /// The median application CPU time exceeds the budget, and exceeds the GPU time.
+    CpuBound { app_cpu_time: f32, app_gpu_time: f32, budget: f32 },
+}
+
+impl PerfAlert {
+    /// A short identifier for the kind of alert, suitable for logs and test reports.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PerfAlert::SustainedAsw { .. } => "sustained_asw",
+            PerfAlert::AswToggling { .. } => "asw_toggling",
+            PerfAlert::FrameDropBurst { .. } => "frame_drop_burst",
+            PerfAlert::QueueAheadGrowing { .. } => "queue_ahead_growing",
+            PerfAlert::GpuBound { .. } => "gpu_bound",
+            PerfAlert::CpuBound { .. } => "cpu_bound",
+        }
+    }
+
+    /// A human readable explanation of the alert and its likely cause.
+    pub fn explanation(&self) -> String {
+        let ms = |t: f32| t * 1000.0;
+        match *self {
+            PerfAlert::SustainedAsw { frames } => format!(
+                "ASW has been active for {} frames: the application is not keeping up with the display and is being \
+                 extrapolated at half rate", frames),
+            PerfAlert::AswToggling { toggles, frames } => format!(
+                "ASW switched on {} times in {} frames: frame times are hovering around the budget, causing visible \
+                 judder as ASW engages and disengages", toggles, frames),
+            PerfAlert::FrameDropBurst { app, compositor, frames } => format!(
+                "{} frames dropped by the application and {} by the compositor in {} frames", app, compositor, frames),
+            PerfAlert::QueueAheadGrowing { from, to } => format!(
+                "queue ahead time grew from {:.2}ms to {:.2}ms: the application is submitting frames further ahead of \
+                 display, adding latency", ms(from), ms(to)),
+            PerfAlert::GpuBound { app_gpu_time, compositor_gpu_time, budget } => format!(
+                "GPU bound: the application takes {:.2}ms of GPU time per frame, but only {:.2}ms of the {:.2}ms \
+                 budget remains after the compositor's {:.2}ms",
+                ms(app_gpu_time), ms(budget - compositor_gpu_time), ms(budget), ms(compositor_gpu_time)),
+            PerfAlert::CpuBound { app_cpu_time, app_gpu_time, budget } => format!(
+                "CPU bound: the application takes {:.2}ms of CPU time per frame against a {:.2}ms budget, while its \
+                 GPU work takes {:.2}ms", ms(app_cpu_time), ms(budget), ms(app_gpu_time)),
+        }
+    }
+}
+
+impl fmt::Display for PerfAlert {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&self.explanation())
+    }
+}
+
+/// The result of analyzing a window of frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Number of frames analyzed.
+    pub frames: usize,
+    /// The problems found, in the order of the [`PerfAlert`](enum.PerfAlert.html) variants.
+    pub alerts: Vec<PerfAlert>,
+    /// The resolution scale to render at relative to the current one, from the latest
+    /// `AdaptiveGpuPerformanceScale`, or `None` if no stats were collected.
+    pub resolution_scale: Option<f32>,
+}
+
+impl Analysis {
+    /// Whether no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        self.alerts.is_empty()
+    }
+}
+
+/// Looks for frame pacing and ASW problems in the frames gathered by a
+/// [`PerfCollector`](struct.PerfCollector.html).
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::perf::{PerfAnalyzer, PerfCollector};
+/// # fn foo(session: Session, mut resolution_scale: f32) -> ovr_sys::error::Result<()> {
+/// let mut perf = PerfCollector::default();
+/// let analyzer = PerfAnalyzer::default();
+/// perf.poll(session)?;
+/// let analysis = analyzer.analyze(&perf);
+/// for alert in &analysis.alerts {
+///     eprintln!("perf: {}: {}", alert.name(), alert);
+/// }
+/// if let Some(scale) = perf.adaptive_gpu_performance_scale() {
+///     resolution_scale = analyzer.recommend_resolution_scale(resolution_scale, scale);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct PerfAnalyzer {
+    /// The thresholds in use.
+    pub config: AnalyzerConfig,
+}
+
+impl PerfAnalyzer {
+    /// Creates an analyzer with the given thresholds.
+    pub fn new(config: AnalyzerConfig) -> PerfAnalyzer {
+        PerfAnalyzer { config }
+    }
+
+    /// Analyzes the most recent frames of a collector.
+    pub fn analyze(&self, collector: &PerfCollector) -> Analysis {
+        let frames = collector.frames();
+        let start = frames.len().saturating_sub(self.config.window);
+        let window: Vec<_> = frames.iter().skip(start).cloned().collect();
+        let mut analysis = self.analyze_frames(&window);
+        analysis.resolution_scale = collector.adaptive_gpu_performance_scale()
+            .map(|scale| self.recommend_resolution_scale(1.0, scale));
+        analysis
+    }
+
+    /// Analyzes a window of frames, oldest first.
+    ///
+    /// The returned `resolution_scale` is `None` since frames do not carry the
+    /// `AdaptiveGpuPerformanceScale`.
+    pub fn analyze_frames(&self, frames: &[ovrPerfStatsPerCompositorFrame]) -> Analysis {
+        let config = &self.config;
+        let mut alerts = Vec::new();
+
+        let asw_run = frames.iter().rev().take_while(|f| f.AswIsActive != ovrFalse).count();
+        if config.asw_sustained_frames > 0 && asw_run >= config.asw_sustained_frames {
+            alerts.push(PerfAlert::SustainedAsw { frames: asw_run });
+        }
+        let toggles = counter_increase(frames, |f| f.AswActivatedToggleCount);
+        if toggles >= config.asw_toggle_threshold && toggles > 0 {
+            alerts.push(PerfAlert::AswToggling { toggles, frames: frames.len() });
+        }
+
+        let app = counter_increase(frames, |f| f.AppDroppedFrameCount);
+        let compositor = counter_increase(frames, |f| f.CompositorDroppedFrameCount);
+        if app + compositor >= config.drop_burst_threshold && app + compositor > 0 {
+            alerts.push(PerfAlert::FrameDropBurst { app, compositor, frames: frames.len() });
+        }
+
+        let (first, second) = frames.split_at(frames.len() / 2);
+        if let (Some(from), Some(to)) = (mean(first.iter().map(|f| f.AppQueueAheadTime)), mean(second.iter().map(|f| f.AppQueueAheadTime))) {
+            if to - from >= config.queue_ahead_growth {
+                alerts.push(PerfAlert::QueueAheadGrowing { from, to });
+            }
+        }
+
+        let median = |time: fn(&ovrPerfStatsPerCompositorFrame) -> f32| Percentiles::of(frames.iter().map(time)).map(|p| p.p50);
+        let cpu = median(|f| f.AppCpuElapsedTime);
+        let gpu = median(|f| f.AppGpuElapsedTime);
+        let compositor_gpu = median(|f| f.CompositorGpuElapsedTime).unwrap_or(0.0);
+        let budget = config.frame_budget;
+        if let Some(gpu) = gpu {
+            if gpu > (budget - compositor_gpu) * config.bound_ratio && gpu >= cpu.unwrap_or(0.0) {
+                alerts.push(PerfAlert::GpuBound { app_gpu_time: gpu, compositor_gpu_time: compositor_gpu, budget });
+            }
+        }
+        if let Some(cpu) = cpu {
+            if cpu > budget * config.bound_ratio && cpu > gpu.unwrap_or(0.0) {
+                alerts.push(PerfAlert::CpuBound { app_cpu_time: cpu, app_gpu_time: gpu.unwrap_or(0.0), budget });
+            }
+        }
+
+        Analysis {
+            frames: frames.len(),
+            alerts,
+            resolution_scale: None,
+        }
+    }
+
+    /// Recommends a resolution scale given the current one and an `AdaptiveGpuPerformanceScale`.
+    ///
+    /// The performance scale is a ratio of GPU work, and GPU work grows with the pixel count, so the
+    /// resolution in each dimension is scaled by its square root. The result is clamped to the
+    /// configured range.
+    pub fn recommend_resolution_scale(&self, current: f32, adaptive_gpu_performance_scale: f32) -> f32 {
+        if adaptive_gpu_performance_scale <= 0.0 || !adaptive_gpu_performance_scale.is_finite() {
+            return current;
+        }
+        (current * adaptive_gpu_performance_scale.sqrt()).clamp(self.config.min_resolution_scale, self.config.max_resolution_scale)
+    }
+}
+
+/// The average of the non-negative values, or `None` if there are none.
+fn mean<I: Iterator<Item = f32>>(values: I) -> Option<f32> {
+    let (sum, count) = values.filter(|v| *v >= 0.0).fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count > 0 {
+        Some(sum / count as f32)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_analyzer() {
+    use super::test_frame;
+
+    let analyzer = PerfAnalyzer::default();
+    let healthy: Vec<_> = (0..90).map(|i| test_frame(i, 0.004, 0.006)).collect();
+    assert!(analyzer.analyze_frames(&healthy).is_healthy());
+
+    // The GPU time creeps over budget: ASW engages for good, frames drop and queue ahead grows.
+    let mut frames = healthy.clone();
+    for (i, frame) in frames.iter_mut().enumerate() {
+        frame.AppGpuElapsedTime = 0.0105;
+        frame.CompositorGpuElapsedTime = 0.001;
+        frame.AppQueueAheadTime = if i < 45 { 0.001 } else { 0.004 };
+        frame.AswIsActive = if i >= 30 { ::ovrTrue } else { ovrFalse };
+        frame.AswActivatedToggleCount = if i >= 30 { 1 } else { 0 };
+        frame.AppDroppedFrameCount = (i / 20) as i32;
+    }
+    let analysis = analyzer.analyze_frames(&frames);
+    let names: Vec<_> = analysis.alerts.iter().map(|a| a.name()).collect();
+    assert_eq!(names, ["sustained_asw", "frame_drop_burst", "queue_ahead_growing", "gpu_bound"]);
+    assert_eq!(analysis.alerts[0], PerfAlert::SustainedAsw { frames: 60 });
+    assert_eq!(analysis.alerts[1], PerfAlert::FrameDropBurst { app: 4, compositor: 0, frames: 90 });
+    assert!(analysis.alerts[3].to_string().starts_with("GPU bound: the application takes 10.50ms of GPU time"));
+
+    let cpu_bound: Vec<_> = (0..90).map(|i| test_frame(i, 0.012, 0.005)).collect();
+    assert_eq!(analyzer.analyze_frames(&cpu_bound).alerts, [PerfAlert::CpuBound { app_cpu_time: 0.012, app_gpu_time: 0.005, budget: 1.0 / 90.0 }]);
+
+    assert_eq!(analyzer.recommend_resolution_scale(1.0, 0.81), 0.9);
+    assert_eq!(analyzer.recommend_resolution_scale(1.2, 4.0), 1.5);
+    assert_eq!(analyzer.recommend_resolution_scale(1.0, 0.0), 1.0);
+}
@@ -0,0 +1,426 @@
+use ::std::collections::VecDeque;
+use ::std::mem;
+
+use ::libc::c_int;
+
+use ::{
+    ovrFalse,
+    ovrPerfStats,
+    ovrPerfStatsPerCompositorFrame,
+    ovr_GetPerfStats,
+    ovr_ResetPerfStats
+};
+use ::error::{
+    check_call,
+    Result
+};
+use ::json::{
+    number,
+    ArrayWriter,
+    ObjectWriter
+};
+use ::session::Session;
+
+mod analyzer;
+mod trace;
+
+pub use self::analyzer::{
+    AnalyzerConfig,
+    Analysis,
+    PerfAlert,
+    PerfAnalyzer
+};
+pub use self::trace::{
+    FrameTiming,
+    TraceRecorder
+};
+
+/// Number of compositor frames kept by [`PerfCollector::default`](struct.PerfCollector.html).
+pub const DEFAULT_HISTORY: usize = 90 * 60;
+
+/// The name of a compositor frame field and how to format it.
+type FrameField = (&'static str, fn(&ovrPerfStatsPerCompositorFrame) -> String);
+
+/// The fields of a compositor frame, in declaration order, as exported to CSV and JSON.
+static FRAME_FIELDS: [FrameField; 18] = [
+    ("HmdVsyncIndex", |f| f.HmdVsyncIndex.to_string()),
+    ("AppFrameIndex", |f| f.AppFrameIndex.to_string()),
+    ("AppDroppedFrameCount", |f| f.AppDroppedFrameCount.to_string()),
+    ("AppMotionToPhotonLatency", |f| number(f.AppMotionToPhotonLatency)),
+    ("AppQueueAheadTime", |f| number(f.AppQueueAheadTime)),
+    ("AppCpuElapsedTime", |f| number(f.AppCpuElapsedTime)),
+    ("AppGpuElapsedTime", |f| number(f.AppGpuElapsedTime)),
+    ("CompositorFrameIndex", |f| f.CompositorFrameIndex.to_string()),
+    ("CompositorDroppedFrameCount", |f| f.CompositorDroppedFrameCount.to_string()),
+    ("CompositorLatency", |f| number(f.CompositorLatency)),
+    ("CompositorCpuElapsedTime", |f| number(f.CompositorCpuElapsedTime)),
+    ("CompositorGpuElapsedTime", |f| number(f.CompositorGpuElapsedTime)),
+    ("CompositorCpuStartToGpuEndElapsedTime", |f| number(f.CompositorCpuStartToGpuEndElapsedTime)),
+    ("CompositorGpuEndToVsyncElapsedTime", |f| number(f.CompositorGpuEndToVsyncElapsedTime)),
+    ("AswIsActive", |f| (f.AswIsActive != ovrFalse).to_string()),
+    ("AswActivatedToggleCount", |f| f.AswActivatedToggleCount.to_string()),
+    ("AswPresentedFrameCount", |f| f.AswPresentedFrameCount.to_string()),
+    ("AswFailedFrameCount", |f| f.AswFailedFrameCount.to_string()),
+];
+
+/// The 50th, 95th and 99th percentiles of a timing, in seconds.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Percentiles {
+    pub p50: f32,
+    pub p95: f32,
+    pub p99: f32,
+}
+
+impl Percentiles {
+    /// Computes nearest-rank percentiles, ignoring negative values which LibOVR uses for
+    /// unavailable timings. Returns `None` if no values remain.
+    pub fn of<I: IntoIterator<Item = f32>>(values: I) -> Option<Percentiles> {
+        let mut values: Vec<f32> = values.into_iter().filter(|v| *v >= 0.0).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        let rank = |p: f32| values[((p / 100.0 * values.len() as f32).ceil() as usize).clamp(1, values.len()) - 1];
+        Some(Percentiles {
+            p50: rank(50.0),
+            p95: rank(95.0),
+            p99: rank(99.0),
+        })
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let mut object = ObjectWriter::new(out);
+        object.raw("p50", &number(self.p50));
+        object.raw("p95", &number(self.p95));
+        object.raw("p99", &number(self.p99));
+        object.finish();
+    }
+}
+
+/// A summary of the frames held by a [`PerfCollector`](struct.PerfCollector.html).
+///
+/// Percentiles are `None` when no frame reported the timing.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct PerfReport {
+    /// Number of compositor frames summarised.
+    pub frames: usize,
+    /// `AppCpuElapsedTime`.
+    pub app_cpu_time: Option<Percentiles>,
+    /// `AppGpuElapsedTime`.
+    pub app_gpu_time: Option<Percentiles>,
+    /// `CompositorLatency`.
+    pub compositor_latency: Option<Percentiles>,
+    /// `AppMotionToPhotonLatency`.
+    pub motion_to_photon_latency: Option<Percentiles>,
+    /// Frames the application failed to submit in time, from `AppDroppedFrameCount`.
+    pub app_dropped_frames: u32,
+    /// Frames the compositor failed to finish in time, from `CompositorDroppedFrameCount`.
+    pub compositor_dropped_frames: u32,
+    /// Times ASW was switched on, from `AswActivatedToggleCount`.
+    pub asw_toggles: u32,
+    /// Number of polls where `ovr_GetPerfStats` reported `AnyFrameStatsDropped`, meaning frames are
+    /// missing from the history.
+    pub stats_dropped: u32,
+    /// Number of times `CompositorFrameIndex` went backwards, meaning the compositor restarted.
+    pub restarts: u32,
+}
+
+impl PerfReport {
+    /// Formats the report as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let mut object = ObjectWriter::new(out);
+        object.raw("frames", &self.frames.to_string());
+        for &(name, percentiles) in &[
+            ("app_cpu_time", self.app_cpu_time),
+            ("app_gpu_time", self.app_gpu_time),
+            ("compositor_latency", self.compositor_latency),
+            ("motion_to_photon_latency", self.motion_to_photon_latency),
+        ] {
+            match percentiles {
+                Some(percentiles) => percentiles.write_json(object.key(name)),
+                None => object.raw(name, "null"),
+            }
+        }
+        object.raw("app_dropped_frames", &self.app_dropped_frames.to_string());
+        object.raw("compositor_dropped_frames", &self.compositor_dropped_frames.to_string());
+        object.raw("asw_toggles", &self.asw_toggles.to_string());
+        object.raw("stats_dropped", &self.stats_dropped.to_string());
+        object.raw("restarts", &self.restarts.to_string());
+        object.finish();
+    }
+}
+
+/// Accumulates the compositor frames reported by `ovr_GetPerfStats` into a rolling history.
+///
+/// Each call to `ovr_GetPerfStats` returns up to `ovrMaxProvidedFrameStats` of the most recent
+/// frames, so consecutive calls overlap. The collector keeps each frame once, by its
+/// `CompositorFrameIndex`. A poll whose most recent frame is older than the last frame kept is
+/// taken to mean that the compositor restarted, and its frames are kept as new.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::perf::PerfCollector;
+/// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+/// let mut perf = PerfCollector::default();
+/// for _ in 0..1000 {
+///     // ... render and submit a frame ...
+///     perf.poll(session)?;
+/// }
+/// let report = perf.report();
+/// if let Some(gpu) = report.app_gpu_time {
+///     println!("app GPU p95: {:.1}ms", gpu.p95 * 1000.0);
+/// }
+/// ::std::fs::write("perf.csv", perf.to_csv()).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PerfCollector {
+    capacity: usize,
+    frames: VecDeque<ovrPerfStatsPerCompositorFrame>,
+    last_index: Option<c_int>,
+    stats_dropped: u32,
+    restarts: u32,
+    adaptive_gpu_performance_scale: Option<f32>,
+}
+
+impl Default for PerfCollector {
+    fn default() -> PerfCollector {
+        PerfCollector::new(DEFAULT_HISTORY)
+    }
+}
+
+impl PerfCollector {
+    /// Creates a collector keeping the latest `capacity` frames.
+    pub fn new(capacity: usize) -> PerfCollector {
+        PerfCollector {
+            capacity,
+            frames: VecDeque::with_capacity(capacity.min(DEFAULT_HISTORY)),
+            last_index: None,
+            stats_dropped: 0,
+            restarts: 0,
+            adaptive_gpu_performance_scale: None,
+        }
+    }
+
+    /// Calls `ovr_GetPerfStats` and adds its frames, returning how many were new.
+    pub fn poll(&mut self, session: Session) -> Result<usize> {
+        let mut stats: ovrPerfStats = unsafe { mem::zeroed() };
+        check_call("ovr_GetPerfStats", String::new, unsafe { ovr_GetPerfStats(session.as_raw(), &mut stats as *mut _) })?;
+        Ok(self.add(&stats))
+    }
+
+    /// Resets the runtime's counters with `ovr_ResetPerfStats` and clears the history.
+    pub fn reset(&mut self, session: Session) -> Result<()> {
+        check_call("ovr_ResetPerfStats", String::new, unsafe { ovr_ResetPerfStats(session.as_raw()) })?;
+        self.clear();
+        Ok(())
+    }
+
+    /// Clears the history and counters.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.last_index = None;
+        self.stats_dropped = 0;
+        self.restarts = 0;
+        self.adaptive_gpu_performance_scale = None;
+    }
+
+    /// Adds the frames of an `ovrPerfStats`, as `poll` does, returning how many were new.
+    pub fn add(&mut self, stats: &ovrPerfStats) -> usize {
+        if stats.AnyFrameStatsDropped != ovrFalse {
+            self.stats_dropped += 1;
+        }
+        self.adaptive_gpu_performance_scale = Some(stats.AdaptiveGpuPerformanceScale);
+        let count = (stats.FrameStatsCount.max(0) as usize).min(stats.FrameStats.len());
+        let mut added = 0;
+        // Entries are most recent first. Consecutive polls overlap, so the most recent frame is only
+        // older than the last one kept when the compositor's frame index has started over.
+        if let (Some(last), Some(newest)) = (self.last_index, stats.FrameStats[..count].first()) {
+            if newest.CompositorFrameIndex < last {
+                self.last_index = None;
+                self.restarts += 1;
+            }
+        }
+        for frame in stats.FrameStats[..count].iter().rev() {
+            let new = match self.last_index {
+                Some(last) => frame.CompositorFrameIndex > last,
+                None => true,
+            };
+            if new {
+                self.last_index = Some(frame.CompositorFrameIndex);
+                if self.frames.len() == self.capacity {
+                    self.frames.pop_front();
+                }
+                if self.capacity > 0 {
+                    self.frames.push_back(*frame);
+                }
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// The frames in the history, oldest first.
+    pub fn frames(&self) -> &VecDeque<ovrPerfStatsPerCompositorFrame> {
+        &self.frames
+    }
+
+    /// The latest `AdaptiveGpuPerformanceScale`, if any stats were added.
+    pub fn adaptive_gpu_performance_scale(&self) -> Option<f32> {
+        self.adaptive_gpu_performance_scale
+    }
+
+    /// Summarises the frames in the history.
+    pub fn report(&self) -> PerfReport {
+        PerfReport {
+            frames: self.frames.len(),
+            app_cpu_time: Percentiles::of(self.frames.iter().map(|f| f.AppCpuElapsedTime)),
+            app_gpu_time: Percentiles::of(self.frames.iter().map(|f| f.AppGpuElapsedTime)),
+            compositor_latency: Percentiles::of(self.frames.iter().map(|f| f.CompositorLatency)),
+            motion_to_photon_latency: Percentiles::of(self.frames.iter().map(|f| f.AppMotionToPhotonLatency)),
+            app_dropped_frames: counter_increase(&self.frames, |f| f.AppDroppedFrameCount),
+            compositor_dropped_frames: counter_increase(&self.frames, |f| f.CompositorDroppedFrameCount),
+            asw_toggles: counter_increase(&self.frames, |f| f.AswActivatedToggleCount),
+            stats_dropped: self.stats_dropped,
+            restarts: self.restarts,
+        }
+    }
+
+    /// Formats the history as CSV, with a header row of `ovrPerfStatsPerCompositorFrame` field names.
+    pub fn to_csv(&self) -> String {
+        let fields = &FRAME_FIELDS;
+        let mut out = fields.iter().map(|&(name, _)| name).collect::<Vec<_>>().join(",");
+        out.push('\n');
+        for frame in &self.frames {
+            out.push_str(&fields.iter().map(|&(_, value)| value(frame)).collect::<Vec<_>>().join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Formats the report and the history as a JSON object with `report` and `frames` members.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        {
+            let mut object = ObjectWriter::new(&mut out);
+            self.report().write_json(object.key("report"));
+            let mut frames = ArrayWriter::new(object.key("frames"));
+            for frame in &self.frames {
+                let mut fields = ObjectWriter::new(frames.item());
+                for &(name, value) in &FRAME_FIELDS {
+                    fields.raw(name, &value(frame));
+                }
+                fields.finish();
+            }
+            frames.finish();
+            object.finish();
+        }
+        out
+    }
+}
+
+/// Sums the increases of a cumulative counter across frames, treating a decrease as the counter
+/// having been reset by `ovr_ResetPerfStats`.
+fn counter_increase<'a, I, F>(frames: I, counter: F) -> u32
+    where I: IntoIterator<Item = &'a ovrPerfStatsPerCompositorFrame>, F: Fn(&ovrPerfStatsPerCompositorFrame) -> c_int
+{
+    let mut total = 0;
+    let mut previous: Option<c_int> = None;
+    for frame in frames {
+        let value = counter(frame);
+        if let Some(previous) = previous {
+            total += if value >= previous { value - previous } else { value.max(0) } as u32;
+        }
+        previous = Some(value);
+    }
+    total
+}
+
+#[cfg(test)]
+pub fn test_frame(index: c_int, cpu: f32, gpu: f32) -> ovrPerfStatsPerCompositorFrame {
+    let mut frame: ovrPerfStatsPerCompositorFrame = unsafe { mem::zeroed() };
+    frame.CompositorFrameIndex = index;
+    frame.HmdVsyncIndex = index;
+    frame.AppFrameIndex = index;
+    frame.AppCpuElapsedTime = cpu;
+    frame.AppGpuElapsedTime = gpu;
+    frame.CompositorLatency = 0.002;
+    frame.AppMotionToPhotonLatency = 0.02;
+    frame
+}
+
+#[cfg(test)]
+pub fn test_stats(frames: &[ovrPerfStatsPerCompositorFrame]) -> ovrPerfStats {
+    let mut stats: ovrPerfStats = unsafe { mem::zeroed() };
+    // Most recent first, as returned by the runtime.
+    for (slot, frame) in stats.FrameStats.iter_mut().zip(frames.iter().rev()) {
+        *slot = *frame;
+    }
+    stats.FrameStatsCount = frames.len().min(stats.FrameStats.len()) as c_int;
+    stats.AdaptiveGpuPerformanceScale = 1.0;
+    stats
+}
+
+#[cfg(test)]
+#[test]
+fn test_collector() {
+    let mut collector = PerfCollector::new(100);
+    let frames: Vec<_> = (0..120).map(|i| test_frame(i, (i % 10) as f32 / 1000.0, 0.008)).collect();
+    // Overlapping windows of five frames, advancing by three.
+    let mut added = 0;
+    for start in (0..116).step_by(3) {
+        added += collector.add(&test_stats(&frames[start..start + 5]));
+    }
+    assert_eq!(added, 119);
+    assert_eq!(collector.frames().len(), 100);
+    assert_eq!(collector.frames()[0].CompositorFrameIndex, 19);
+
+    let report = collector.report();
+    assert_eq!(report.frames, 100);
+    assert_eq!(report.app_cpu_time, Some(Percentiles { p50: 0.004, p95: 0.009, p99: 0.009 }));
+    assert_eq!(report.app_gpu_time.unwrap().p99, 0.008);
+    assert_eq!(Percentiles::of(vec![-1.0, -1.0]), None);
+    assert_eq!(Percentiles::of((1..=100).map(|i| i as f32)), Some(Percentiles { p50: 50.0, p95: 95.0, p99: 99.0 }));
+
+    let csv = collector.to_csv();
+    assert_eq!(csv.lines().count(), 101);
+    assert!(csv.starts_with("HmdVsyncIndex,AppFrameIndex,"));
+    assert!(csv.lines().nth(1).unwrap().starts_with("19,19,0,0.02,0,0.009,0.008,19,"));
+    let json = collector.to_json();
+    assert!(json.starts_with(r#"{"report":{"frames":100,"app_cpu_time":{"p50":0.004,"p95":0.009,"p99":0.009},"#));
+    assert!(json.contains(r#"{"HmdVsyncIndex":19,"AppFrameIndex":19,"#));
+}
+
+#[cfg(test)]
+#[test]
+fn test_counters() {
+    let mut frames: Vec<_> = (0..6).map(|i| test_frame(i, 0.005, 0.005)).collect();
+    for (frame, &(dropped, toggles)) in frames.iter_mut().zip(&[(3, 0), (3, 1), (5, 1), (0, 0), (1, 2), (1, 2)]) {
+        frame.AppDroppedFrameCount = dropped;
+        frame.AswActivatedToggleCount = toggles;
+    }
+    let mut collector = PerfCollector::default();
+    let mut stats = test_stats(&frames[..5]);
+    stats.AnyFrameStatsDropped = ::ovrTrue;
+    collector.add(&stats);
+    collector.add(&test_stats(&frames[1..]));
+    let report = collector.report();
+    // The counters were reset between the third and fourth frames.
+    assert_eq!(report.app_dropped_frames, 3);
+    assert_eq!(report.asw_toggles, 3);
+    assert_eq!(report.stats_dropped, 1);
+    assert_eq!(report.restarts, 0);
+
+    // The compositor restarted, so its frame indices started over.
+    assert_eq!(collector.add(&test_stats(&frames[..2])), 2);
+    assert_eq!(collector.add(&test_stats(&frames[..3])), 1);
+    assert_eq!(collector.frames().len(), 9);
+    assert_eq!(collector.report().restarts, 1);
+}
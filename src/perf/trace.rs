@@ -0,0 +1,277 @@
+use ::std::collections::BTreeMap;
+
+use ::libc::{
+    c_int,
+    c_longlong
+};
+
+use ::{
+    ovrFalse,
+    ovrPerfStatsPerCompositorFrame,
+    ovr_GetPredictedDisplayTime,
+    ovr_GetTimeInSeconds
+};
+use ::json::{
+    number,
+    write_string,
+    ArrayWriter,
+    ObjectWriter
+};
+use ::session::Session;
+use super::PerfCollector;
+
+/// Thread ids of the tracks in the exported trace.
+const APP_CPU_TRACK: u32 = 1;
+const APP_GPU_TRACK: u32 = 2;
+const COMPOSITOR_TRACK: u32 = 3;
+const VSYNC_TRACK: u32 = 4;
+
+/// Timestamps of one application frame, in seconds as returned by `ovr_GetTimeInSeconds`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrameTiming {
+    /// The frame index passed to `ovr_GetPredictedDisplayTime` and `ovr_SubmitFrame`.
+    pub frame_index: i64,
+    /// When the application started working on the frame.
+    pub begin: f64,
+    /// When the application submitted the frame.
+    pub submit: f64,
+    /// The display time predicted for the frame by `ovr_GetPredictedDisplayTime`.
+    pub predicted_display: f64,
+}
+
+impl FrameTiming {
+    /// Starts timing a frame now, querying its predicted display time.
+    pub fn begin(session: Session, frame_index: i64) -> FrameTiming {
+        let begin = unsafe { ovr_GetTimeInSeconds() };
+        let predicted_display = unsafe { ovr_GetPredictedDisplayTime(session.as_raw(), frame_index as c_longlong) };
+        FrameTiming {
+            frame_index,
+            begin,
+            submit: begin,
+            predicted_display,
+        }
+    }
+
+    /// Marks the frame as submitted now.
+    pub fn submitted(&mut self) {
+        self.submit = unsafe { ovr_GetTimeInSeconds() };
+    }
+}
+
+/// Builds a timeline of VR frame timing in the Chrome trace event format, which can be opened in
+/// `chrome://tracing` and in the Perfetto UI.
+///
+/// The trace has four tracks:
+///
+/// * App CPU: each frame from [`FrameTiming::begin`](struct.FrameTiming.html#structfield.begin) to
+///   its submission.
+/// * App GPU: `AppGpuElapsedTime`, placed from the frame's submission since the GPU cannot start
+///   on the frame's commands before then.
+/// * Compositor: `CompositorCpuStartToGpuEndElapsedTime`, ending
+///   `CompositorGpuEndToVsyncElapsedTime` before the vsync.
+/// * VSync: an instant for each vsync a compositor frame was reported for.
+///
+/// `ovrPerfStats` only gives vsync indices, so vsync times are estimated from the predicted display
+/// time of the latest application frame the perf stats refer to, stepping by the vsync interval.
+/// Without such a frame only the application tracks are written.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::perf::{FrameTiming, PerfCollector, TraceRecorder};
+/// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+/// let mut perf = PerfCollector::default();
+/// let mut trace = TraceRecorder::new(1.0 / 90.0);
+/// for frame_index in 0..900 {
+///     let mut timing = FrameTiming::begin(session, frame_index);
+///     // ... render and submit the frame ...
+///     timing.submitted();
+///     trace.record_frame(timing);
+///     perf.poll(session)?;
+/// }
+/// trace.add_perf(&perf);
+/// ::std::fs::write("frames.json", trace.to_json()).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TraceRecorder {
+    vsync_interval: f64,
+    frames: BTreeMap<i64, FrameTiming>,
+    perf: BTreeMap<c_int, ovrPerfStatsPerCompositorFrame>,
+}
+
+impl TraceRecorder {
+    /// Creates an empty recorder for a display refreshing every `vsync_interval` seconds.
+    pub fn new(vsync_interval: f64) -> TraceRecorder {
+        TraceRecorder {
+            vsync_interval,
+            frames: BTreeMap::new(),
+            perf: BTreeMap::new(),
+        }
+    }
+
+    /// Records the timing of an application frame, replacing any earlier timing for its index.
+    pub fn record_frame(&mut self, timing: FrameTiming) {
+        self.frames.insert(timing.frame_index, timing);
+    }
+
+    /// Adds the compositor frames of a collector that were not added yet.
+    pub fn add_perf(&mut self, collector: &PerfCollector) {
+        self.add_perf_frames(collector.frames());
+    }
+
+    /// Adds compositor frames, by their `CompositorFrameIndex`.
+    pub fn add_perf_frames<'a, I: IntoIterator<Item = &'a ovrPerfStatsPerCompositorFrame>>(&mut self, frames: I) {
+        for frame in frames {
+            self.perf.insert(frame.CompositorFrameIndex, *frame);
+        }
+    }
+
+    /// Removes everything recorded.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.perf.clear();
+    }
+
+    /// The estimated time of vsync index 0, or `None` if no recorded frame ties vsyncs to time.
+    fn vsync_origin(&self) -> Option<f64> {
+        let (anchor_index, anchor_time) = self.perf.values().rev()
+            .filter_map(|p| self.frames.get(&(p.AppFrameIndex as i64)).map(|f| (p.HmdVsyncIndex, f.predicted_display)))
+            .next()?;
+        Some(anchor_time - anchor_index as f64 * self.vsync_interval)
+    }
+
+    /// Formats the timeline as a Chrome trace event JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        {
+            let mut object = ObjectWriter::new(&mut out);
+            {
+                let mut events = ArrayWriter::new(object.key("traceEvents"));
+                metadata(events.item(), "process_name", 0, "LibOVR");
+                for &(track, name) in &[(APP_CPU_TRACK, "App CPU"), (APP_GPU_TRACK, "App GPU"), (COMPOSITOR_TRACK, "Compositor"), (VSYNC_TRACK, "VSync")] {
+                    metadata(events.item(), "thread_name", track, name);
+                }
+                for frame in self.frames.values() {
+                    complete(events.item(), APP_CPU_TRACK, &format!("Frame {}", frame.frame_index), frame.begin, frame.submit - frame.begin, &[
+                        ("predicted_display_time", number(frame.predicted_display)),
+                    ]);
+                }
+                let vsync_origin = self.vsync_origin();
+                for perf in self.perf.values() {
+                    if let Some(frame) = self.frames.get(&(perf.AppFrameIndex as i64)) {
+                        if perf.AppGpuElapsedTime >= 0.0 {
+                            complete(events.item(), APP_GPU_TRACK, &format!("Frame {}", frame.frame_index), frame.submit, perf.AppGpuElapsedTime as f64, &[
+                                ("app_dropped_frames", perf.AppDroppedFrameCount.to_string()),
+                                ("queue_ahead_time", number(perf.AppQueueAheadTime)),
+                                ("motion_to_photon_latency", number(perf.AppMotionToPhotonLatency)),
+                            ]);
+                        }
+                    }
+                    let vsync = match vsync_origin {
+                        Some(origin) => origin + perf.HmdVsyncIndex as f64 * self.vsync_interval,
+                        None => continue,
+                    };
+                    if perf.CompositorCpuStartToGpuEndElapsedTime >= 0.0 && perf.CompositorGpuEndToVsyncElapsedTime >= 0.0 {
+                        let end = vsync - perf.CompositorGpuEndToVsyncElapsedTime as f64;
+                        let duration = perf.CompositorCpuStartToGpuEndElapsedTime as f64;
+                        complete(events.item(), COMPOSITOR_TRACK, &format!("Compositor {}", perf.CompositorFrameIndex), end - duration, duration, &[
+                            ("cpu_time", number(perf.CompositorCpuElapsedTime)),
+                            ("gpu_time", number(perf.CompositorGpuElapsedTime)),
+                            ("latency", number(perf.CompositorLatency)),
+                            ("dropped_frames", perf.CompositorDroppedFrameCount.to_string()),
+                            ("asw_active", (perf.AswIsActive != ovrFalse).to_string()),
+                        ]);
+                    }
+                    let mut instant = event(events.item(), VSYNC_TRACK, "i", &format!("VSync {}", perf.HmdVsyncIndex), vsync);
+                    instant.raw("s", "\"t\"");
+                    instant.finish();
+                }
+                events.finish();
+            }
+            object.raw("displayTimeUnit", "\"ms\"");
+            object.finish();
+        }
+        out
+    }
+}
+
+/// Converts seconds to the microseconds used by trace events, rounded to the nanosecond.
+fn microseconds(seconds: f64) -> f64 {
+    (seconds * 1e9).round() / 1e3
+}
+
+/// Starts an event of phase `ph` at `time` seconds, leaving it open for more members.
+fn event<'a>(out: &'a mut String, track: u32, ph: &str, name: &str, time: f64) -> ObjectWriter<'a> {
+    let mut object = ObjectWriter::new(out);
+    write_string(object.key("name"), name);
+    write_string(object.key("ph"), ph);
+    object.raw("pid", "1");
+    object.raw("tid", &track.to_string());
+    object.raw("ts", &number(microseconds(time)));
+    object
+}
+
+/// Writes a complete event lasting `duration` seconds.
+fn complete(out: &mut String, track: u32, name: &str, time: f64, duration: f64, args: &[(&str, String)]) {
+    let mut object = event(out, track, "X", name, time);
+    object.raw("dur", &number(microseconds(duration.max(0.0))));
+    let mut members = ObjectWriter::new(object.key("args"));
+    for &(name, ref value) in args {
+        members.raw(name, value);
+    }
+    members.finish();
+    object.finish();
+}
+
+/// Writes a metadata event naming the process or a track.
+fn metadata(out: &mut String, kind: &str, track: u32, value: &str) {
+    let mut object = ObjectWriter::new(out);
+    object.raw("ph", "\"M\"");
+    write_string(object.key("name"), kind);
+    object.raw("pid", "1");
+    object.raw("tid", &track.to_string());
+    let mut args = ObjectWriter::new(object.key("args"));
+    write_string(args.key("name"), value);
+    args.finish();
+    object.finish();
+}
+
+#[cfg(test)]
+#[test]
+fn test_trace() {
+    use super::test_frame;
+
+    let mut trace = TraceRecorder::new(0.01);
+    for i in 0..3 {
+        let begin = 1.0 + i as f64 * 0.01;
+        trace.record_frame(FrameTiming { frame_index: i, begin, submit: begin + 0.004, predicted_display: begin + 0.02 });
+    }
+    let perf: Vec<_> = (0..3).map(|i| {
+        let mut frame = test_frame(i, 0.004, 0.005);
+        frame.HmdVsyncIndex = 100 + i;
+        frame.CompositorCpuStartToGpuEndElapsedTime = 0.002;
+        frame.CompositorGpuEndToVsyncElapsedTime = 0.001;
+        frame
+    }).collect();
+    trace.add_perf_frames(&perf);
+
+    let json = trace.to_json();
+    assert!(json.starts_with(r#"{"traceEvents":[{"ph":"M","name":"process_name","pid":1,"tid":0,"args":{"name":"LibOVR"}},"#));
+    assert!(json.ends_with(r#"],"displayTimeUnit":"ms"}"#));
+    assert!(json.contains(r#"{"ph":"M","name":"thread_name","pid":1,"tid":3,"args":{"name":"Compositor"}}"#));
+    assert_eq!(json.matches(r#""ph":"X","pid":1,"tid":1,"#).count(), 3);
+    assert_eq!(json.matches(r#""ph":"X","pid":1,"tid":2,"#).count(), 3);
+    assert_eq!(json.matches(r#""ph":"X","pid":1,"tid":3,"#).count(), 3);
+    assert_eq!(json.matches(r#""ph":"i","pid":1,"tid":4,"#).count(), 3);
+    // The second frame is displayed at 1.03s, its GPU work follows its submission at 1.014s and the
+    // compositor finishes 1ms before the vsync after 2ms of work.
+    assert!(json.contains(r#"{"name":"VSync 101","ph":"i","pid":1,"tid":4,"ts":1030000"#));
+    assert!(json.contains(r#"{"name":"Frame 1","ph":"X","pid":1,"tid":2,"ts":1014000"#));
+    assert!(json.contains(r#"{"name":"Compositor 1","ph":"X","pid":1,"tid":3,"ts":1027000"#));
+
+    // Without frame timings there is nothing to place the compositor frames with.
+    let mut untimed = TraceRecorder::new(0.01);
+    untimed.add_perf_frames(&perf);
+    assert!(!untimed.to_json().contains(r#""tid":3,"ts""#));
+}
@@ -0,0 +1,212 @@
+use ::{
+    ovrPosef,
+    ovrQuatf,
+    ovrVector3f,
+    OVR_DEFAULT_EYE_HEIGHT,
+    OVR_DEFAULT_NECK_TO_EYE_HORIZONTAL,
+    OVR_DEFAULT_NECK_TO_EYE_VERTICAL,
+    OVR_DEFAULT_PLAYER_HEIGHT
+};
+use ::math::{
+    rotate3,
+    sub3,
+    vec3
+};
+use ::properties::{
+    EyeHeight,
+    EyeToNoseDistance,
+    Gender as GenderKey,
+    Name,
+    NeckToEyeDistance,
+    PlayerHeight,
+    User
+};
+use ::session::Session;
+
+/// Fraction of the standing eye height above which the user is taken to be standing, halfway
+/// between a typical seated eye height and the standing one.
+const STANDING_THRESHOLD: f32 = 0.85;
+
+/// The gender stored in the user profile, `OVR_KEY_GENDER`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Gender {
+    Male,
+    Female,
+    /// Not specified, or a value not known to this crate. This is `OVR_DEFAULT_GENDER`.
+    Unknown,
+}
+
+impl Gender {
+    /// The value stored in the profile.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Gender::Male => "Male",
+            Gender::Female => "Female",
+            Gender::Unknown => "Unknown",
+        }
+    }
+
+    /// The gender for a value stored in the profile, `Unknown` for unrecognised values.
+    pub fn from_name(value: &str) -> Gender {
+        match value {
+            "Male" => Gender::Male,
+            "Female" => Gender::Female,
+            _ => Gender::Unknown,
+        }
+    }
+}
+
+/// Whether the user is seated or standing, as estimated by
+/// [`UserProfile::posture`](struct.UserProfile.html#method.posture).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Posture {
+    Seated,
+    Standing,
+}
+
+/// The user's profile as configured in the Oculus app.
+///
+/// Missing values, and heights and distances that are not positive, are replaced by the
+/// `OVR_DEFAULT_*` values. LibOVR has no default for the eye to nose distance, which is left at
+/// zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserProfile {
+    /// `OVR_KEY_USER`.
+    pub user: String,
+    /// `OVR_KEY_NAME`.
+    pub name: String,
+    /// `OVR_KEY_GENDER`.
+    pub gender: Gender,
+    /// Height of the user in meters, `OVR_KEY_PLAYER_HEIGHT`.
+    pub player_height: f32,
+    /// Height of the user's eyes above the floor in meters when standing, `OVR_KEY_EYE_HEIGHT`.
+    pub eye_height: f32,
+    /// Horizontal and vertical distance from the neck pivot to the center of the eyes in meters,
+    /// `OVR_KEY_NECK_TO_EYE_DISTANCE`.
+    pub neck_to_eye: [f32; 2],
+    /// Horizontal and vertical distance from the center of the eyes to the tip of the nose in
+    /// meters, `OVR_KEY_EYE_TO_NOSE_DISTANCE`.
+    pub eye_to_nose: [f32; 2],
+}
+
+impl Default for UserProfile {
+    fn default() -> UserProfile {
+        UserProfile {
+            user: String::new(),
+            name: String::new(),
+            gender: Gender::Unknown,
+            player_height: OVR_DEFAULT_PLAYER_HEIGHT,
+            eye_height: OVR_DEFAULT_EYE_HEIGHT,
+            neck_to_eye: [OVR_DEFAULT_NECK_TO_EYE_HORIZONTAL, OVR_DEFAULT_NECK_TO_EYE_VERTICAL],
+            eye_to_nose: [0.0, 0.0],
+        }
+    }
+}
+
+impl Session {
+    /// Reads the user's profile.
+    pub fn user_profile(&self) -> UserProfile {
+        UserProfile {
+            user: self.get(User),
+            name: self.get(Name),
+            gender: Gender::from_name(&self.get(GenderKey)),
+            player_height: self.get(PlayerHeight),
+            eye_height: self.get(EyeHeight),
+            neck_to_eye: self.get(NeckToEyeDistance),
+            eye_to_nose: self.get(EyeToNoseDistance),
+        }.sanitized()
+    }
+}
+
+impl UserProfile {
+    /// Replaces values that are not positive or not finite by their defaults.
+    fn sanitized(mut self) -> UserProfile {
+        let defaults = UserProfile::default();
+        let valid = |v: f32| v.is_finite() && v > 0.0;
+        if !valid(self.player_height) {
+            self.player_height = defaults.player_height;
+        }
+        if !valid(self.eye_height) {
+            self.eye_height = defaults.eye_height;
+        }
+        if !self.neck_to_eye.iter().all(|v| valid(*v)) {
+            self.neck_to_eye = defaults.neck_to_eye;
+        }
+        if !self.eye_to_nose.iter().all(|v| v.is_finite() && *v >= 0.0) {
+            self.eye_to_nose = defaults.eye_to_nose;
+        }
+        self
+    }
+
+    /// The offset from the neck pivot to the center of the eyes, in head space: up by the vertical
+    /// distance and forward, along -Z, by the horizontal one.
+    pub fn neck_to_eye_offset(&self) -> ovrVector3f {
+        vec3(0.0, self.neck_to_eye[1], -self.neck_to_eye[0])
+    }
+
+    /// The pose of the center of the eyes relative to the neck pivot, for applying a neck model
+    /// to orientation-only tracking.
+    pub fn neck_model_pose(&self) -> ovrPosef {
+        ovrPosef {
+            _align: [],
+            Orientation: ovrQuatf { _align: [], x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            Position: self.neck_to_eye_offset(),
+        }
+    }
+
+    /// The position of the neck pivot given the pose of the center of the eyes.
+    pub fn neck_position(&self, eye_pose: &ovrPosef) -> ovrVector3f {
+        sub3(eye_pose.Position, rotate3(eye_pose.Orientation, self.neck_to_eye_offset()))
+    }
+
+    /// The uniform scale that makes an avatar `avatar_height` meters tall match the user's height,
+    /// or 1 if `avatar_height` is not positive.
+    pub fn avatar_scale(&self, avatar_height: f32) -> f32 {
+        if avatar_height > 0.0 {
+            self.player_height / avatar_height
+        } else {
+            1.0
+        }
+    }
+
+    /// Estimates whether the user is seated or standing from the height of their eyes above the
+    /// floor, such as the HMD height in the floor level tracking origin.
+    pub fn posture(&self, eye_height_above_floor: f32) -> Posture {
+        if eye_height_above_floor >= self.eye_height * STANDING_THRESHOLD {
+            Posture::Standing
+        } else {
+            Posture::Seated
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_user_profile() {
+    let profile = UserProfile {
+        player_height: -1.0,
+        eye_height: 1.5,
+        neck_to_eye: [0.1, 0.0],
+        ..UserProfile::default()
+    }.sanitized();
+    assert_eq!(profile.player_height, OVR_DEFAULT_PLAYER_HEIGHT);
+    assert_eq!(profile.eye_height, 1.5);
+    assert_eq!(profile.neck_to_eye, [0.0805, 0.075]);
+    assert_eq!(Gender::from_name(Gender::Female.as_str()), Gender::Female);
+    assert_eq!(Gender::from_name("Other"), Gender::Unknown);
+
+    // Looking straight down, the neck is above the eyes and behind them.
+    let half = (-45.0f32).to_radians();
+    let eyes = ovrPosef {
+        _align: [],
+        Orientation: ovrQuatf { _align: [], x: half.sin(), y: 0.0, z: 0.0, w: half.cos() },
+        Position: vec3(0.0, 1.5, 0.0),
+    };
+    let neck = profile.neck_position(&eyes);
+    assert!(neck.x.abs() < 1e-5 && (neck.y - 1.5 - 0.0805).abs() < 1e-5 && (neck.z - 0.075).abs() < 1e-5);
+
+    assert_eq!(profile.avatar_scale(1.778), 1.0);
+    assert_eq!(profile.avatar_scale(0.0), 1.0);
+    assert_eq!(profile.posture(1.45), Posture::Standing);
+    assert_eq!(profile.posture(1.1), Posture::Seated);
+}
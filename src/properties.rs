@@ -0,0 +1,274 @@
+use ::std::ffi::{
+    CStr,
+    CString
+};
+use ::std::sync::Mutex;
+
+use ::libc::{
+    c_char,
+    c_int,
+    c_uint
+};
+
+use ::{
+    ovrError_InvalidOperation,
+    ovrError_InvalidParameter,
+    ovrFalse,
+    ovrSession,
+    ovrTrue,
+    ovr_GetBool,
+    ovr_GetFloat,
+    ovr_GetFloatArray,
+    ovr_GetInt,
+    ovr_GetString,
+    ovr_SetBool,
+    ovr_SetFloat,
+    ovr_SetFloatArray,
+    ovr_SetInt,
+    ovr_SetString
+};
+use ::error::{
+    Error,
+    Result
+};
+use ::hud::{
+    LayerHudMode,
+    PerfHudMode,
+    StereoHudMode
+};
+use ::session::Session;
+
+/// Serialises `ovr_GetString` calls, whose result is only valid until the next call.
+static GET_STRING: Mutex<()> = Mutex::new(());
+
+/// A type that can be stored in a LibOVR property, routed to the matching `ovr_Get*`/`ovr_Set*`
+/// pair.
+///
+/// Implemented for `bool`, `i32`, `f32`, `[f32; N]`, `String` and the HUD modes.
+pub trait PropertyValue: Sized {
+    /// Reads the property, returning `default` if it does not exist.
+    ///
+    /// # Safety
+    ///
+    /// `session` must be a valid session.
+    unsafe fn get(session: ovrSession, key: &CStr, default: Self) -> Self;
+
+    /// Writes the property, returning `Ok(false)` if the runtime refused it.
+    ///
+    /// # Safety
+    ///
+    /// `session` must be a valid session.
+    unsafe fn set(session: ovrSession, key: &CStr, value: &Self) -> Result<bool>;
+}
+
+impl PropertyValue for bool {
+    unsafe fn get(session: ovrSession, key: &CStr, default: bool) -> bool {
+        ovr_GetBool(session, key.as_ptr(), if default { ovrTrue } else { ovrFalse }) != ovrFalse
+    }
+
+    unsafe fn set(session: ovrSession, key: &CStr, value: &bool) -> Result<bool> {
+        Ok(ovr_SetBool(session, key.as_ptr(), if *value { ovrTrue } else { ovrFalse }) != ovrFalse)
+    }
+}
+
+impl PropertyValue for c_int {
+    unsafe fn get(session: ovrSession, key: &CStr, default: c_int) -> c_int {
+        ovr_GetInt(session, key.as_ptr(), default)
+    }
+
+    unsafe fn set(session: ovrSession, key: &CStr, value: &c_int) -> Result<bool> {
+        Ok(ovr_SetInt(session, key.as_ptr(), *value) != ovrFalse)
+    }
+}
+
+impl PropertyValue for f32 {
+    unsafe fn get(session: ovrSession, key: &CStr, default: f32) -> f32 {
+        ovr_GetFloat(session, key.as_ptr(), default)
+    }
+
+    unsafe fn set(session: ovrSession, key: &CStr, value: &f32) -> Result<bool> {
+        Ok(ovr_SetFloat(session, key.as_ptr(), *value) != ovrFalse)
+    }
+}
+
+/// Float arrays are read with `ovr_GetFloatArray`. If the property holds fewer than `N` values the
+/// default is returned, since a partially filled array has no meaningful value.
+impl<const N: usize> PropertyValue for [f32; N] {
+    unsafe fn get(session: ovrSession, key: &CStr, default: [f32; N]) -> [f32; N] {
+        let mut values = [0.0; N];
+        let read = ovr_GetFloatArray(session, key.as_ptr(), values.as_mut_ptr(), N as c_uint);
+        if read as usize >= N {
+            values
+        } else {
+            default
+        }
+    }
+
+    unsafe fn set(session: ovrSession, key: &CStr, value: &[f32; N]) -> Result<bool> {
+        Ok(ovr_SetFloatArray(session, key.as_ptr(), value.as_ptr(), N as c_uint) != ovrFalse)
+    }
+}
+
+/// Strings are copied out of the runtime's buffer while holding a lock, since the buffer is
+/// reused by the next `ovr_GetString` call. Invalid UTF-8 is replaced. Writing a string containing
+/// a nul character fails with `ovrError_InvalidParameter`.
+impl PropertyValue for String {
+    unsafe fn get(session: ovrSession, key: &CStr, default: String) -> String {
+        let _lock = GET_STRING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let value = ovr_GetString(session, key.as_ptr(), ::std::ptr::null());
+        if value.is_null() {
+            default
+        } else {
+            CStr::from_ptr(value as *const c_char).to_string_lossy().into_owned()
+        }
+    }
+
+    unsafe fn set(session: ovrSession, key: &CStr, value: &String) -> Result<bool> {
+        let value = c_string(key, value)?;
+        Ok(ovr_SetString(session, key.as_ptr(), value.as_ptr()) != ovrFalse)
+    }
+}
+
+fn c_string(key: &CStr, value: &str) -> Result<CString> {
+    CString::new(value.as_bytes())
+        .map_err(|_| Error::new(ovrError_InvalidParameter, format!("value of `{}` contains a nul character", key.to_string_lossy())))
+}
+
+macro_rules! mode_value {
+    ($($mode:ident),*) => {$(
+        /// Stored as its raw integer value. Unknown values read as the default.
+        impl PropertyValue for $mode {
+            unsafe fn get(session: ovrSession, key: &CStr, default: $mode) -> $mode {
+                $mode::from_raw(ovr_GetInt(session, key.as_ptr(), default.as_raw())).unwrap_or(default)
+            }
+
+            unsafe fn set(session: ovrSession, key: &CStr, value: &$mode) -> Result<bool> {
+                Ok(ovr_SetInt(session, key.as_ptr(), value.as_raw()) != ovrFalse)
+            }
+        }
+    )*}
+}
+
+mode_value!(PerfHudMode, LayerHudMode, StereoHudMode);
+
+/// A LibOVR property key together with the type and default of its value.
+///
+/// The keys of `OVR_CAPI_Keys.h` are provided as unit structs in this module. Other keys can be
+/// added by implementing this trait.
+pub trait Property {
+    /// The type stored in the property.
+    type Value: PropertyValue;
+
+    /// The name of the property.
+    fn key(&self) -> &CStr;
+
+    /// The value returned when the property does not exist.
+    fn default_value(&self) -> Self::Value;
+}
+
+macro_rules! properties {
+    ($($(#[$attr:meta])* $name:ident: $value:ty = $key:ident, $default:expr;)*) => {$(
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+        pub struct $name;
+
+        impl Property for $name {
+            type Value = $value;
+
+            fn key(&self) -> &CStr {
+                CStr::from_bytes_with_nul(::$key).expect("property keys are nul terminated")
+            }
+
+            fn default_value(&self) -> $value {
+                $default
+            }
+        }
+    )*}
+}
+
+properties! {
+    /// The current user, `OVR_KEY_USER`.
+    User: String = OVR_KEY_USER, String::new();
+    /// The user's name, `OVR_KEY_NAME`.
+    Name: String = OVR_KEY_NAME, String::new();
+    /// The user's gender, `OVR_KEY_GENDER`: `"Male"`, `"Female"` or `"Unknown"`.
+    Gender: String = OVR_KEY_GENDER, CStr::from_bytes_with_nul(::OVR_DEFAULT_GENDER).expect("OVR_DEFAULT_GENDER is nul terminated").to_string_lossy().into_owned();
+    /// The user's height in meters, `OVR_KEY_PLAYER_HEIGHT`.
+    PlayerHeight: f32 = OVR_KEY_PLAYER_HEIGHT, ::OVR_DEFAULT_PLAYER_HEIGHT;
+    /// The height of the user's eyes in meters, `OVR_KEY_EYE_HEIGHT`.
+    EyeHeight: f32 = OVR_KEY_EYE_HEIGHT, ::OVR_DEFAULT_EYE_HEIGHT;
+    /// Horizontal and vertical distance from the neck pivot to the eyes in meters,
+    /// `OVR_KEY_NECK_TO_EYE_DISTANCE`.
+    NeckToEyeDistance: [f32; 2] = OVR_KEY_NECK_TO_EYE_DISTANCE, [::OVR_DEFAULT_NECK_TO_EYE_HORIZONTAL, ::OVR_DEFAULT_NECK_TO_EYE_VERTICAL];
+    /// Horizontal and vertical distance from the eyes to the nose in meters,
+    /// `OVR_KEY_EYE_TO_NOSE_DISTANCE`. LibOVR defines no default, zero is used.
+    EyeToNoseDistance: [f32; 2] = OVR_KEY_EYE_TO_NOSE_DISTANCE, [0.0, 0.0];
+    /// The Performance HUD page, `OVR_PERF_HUD_MODE`.
+    PerfHud: PerfHudMode = OVR_PERF_HUD_MODE, PerfHudMode::Off;
+    /// The Layer HUD mode, `OVR_LAYER_HUD_MODE`.
+    LayerHud: LayerHudMode = OVR_LAYER_HUD_MODE, LayerHudMode::Off;
+    /// The layer described by the Layer HUD, `OVR_LAYER_HUD_CURRENT_LAYER`.
+    LayerHudCurrentLayer: c_int = OVR_LAYER_HUD_CURRENT_LAYER, 0;
+    /// Whether the Layer HUD leaves the other layers visible, `OVR_LAYER_HUD_SHOW_ALL_LAYERS`.
+    LayerHudShowAllLayers: bool = OVR_LAYER_HUD_SHOW_ALL_LAYERS, false;
+    /// The Debug Stereo HUD mode, `OVR_DEBUG_HUD_STEREO_MODE`.
+    StereoHud: StereoHudMode = OVR_DEBUG_HUD_STEREO_MODE, StereoHudMode::Off;
+    /// Whether the stereo guide shows information about itself, `OVR_DEBUG_HUD_STEREO_GUIDE_INFO_ENABLE`.
+    StereoGuideInfoEnable: bool = OVR_DEBUG_HUD_STEREO_GUIDE_INFO_ENABLE, false;
+    /// Width and height of the stereo guide quad in meters, `OVR_DEBUG_HUD_STEREO_GUIDE_SIZE`.
+    StereoGuideSize: [f32; 2] = OVR_DEBUG_HUD_STEREO_GUIDE_SIZE, [0.0; 2];
+    /// Position of the stereo guide in meters, `OVR_DEBUG_HUD_STEREO_GUIDE_POSITION`.
+    StereoGuidePosition: [f32; 3] = OVR_DEBUG_HUD_STEREO_GUIDE_POSITION, [0.0; 3];
+    /// Yaw, pitch and roll of the stereo guide in radians, `OVR_DEBUG_HUD_STEREO_GUIDE_YAWPITCHROLL`.
+    StereoGuideYawPitchRoll: [f32; 3] = OVR_DEBUG_HUD_STEREO_GUIDE_YAWPITCHROLL, [0.0; 3];
+    /// RGBA colour of the stereo guide, `OVR_DEBUG_HUD_STEREO_GUIDE_COLOR`.
+    StereoGuideColor: [f32; 4] = OVR_DEBUG_HUD_STEREO_GUIDE_COLOR, [0.0; 4];
+}
+
+impl Session {
+    /// Reads a property, or its default if it does not exist.
+    ///
+    /// ```no_run
+    /// # use ovr_sys::session::Session;
+    /// # use ovr_sys::properties::{NeckToEyeDistance, PlayerHeight, User};
+    /// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+    /// let height: f32 = session.get(PlayerHeight);
+    /// let [horizontal, vertical] = session.get(NeckToEyeDistance);
+    /// let user: String = session.get(User);
+    /// session.set(PlayerHeight, 1.85)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<P: Property>(&self, property: P) -> P::Value {
+        unsafe { P::Value::get(self.as_raw(), property.key(), property.default_value()) }
+    }
+
+    /// Writes a property.
+    ///
+    /// Fails with `ovrError_InvalidOperation` if the runtime refuses the value, which LibOVR
+    /// documents as happening for read-only properties.
+    pub fn set<P: Property>(&self, property: P, value: P::Value) -> Result<()> {
+        let key = property.key();
+        if unsafe { P::Value::set(self.as_raw(), key, &value)? } {
+            Ok(())
+        } else {
+            Err(Error::new(ovrError_InvalidOperation, format!("the runtime refused to set `{}`", key.to_string_lossy())))
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_properties() {
+    assert_eq!(PlayerHeight.key().to_bytes(), b"PlayerHeight");
+    assert_eq!(PlayerHeight.default_value(), 1.778);
+    assert_eq!(NeckToEyeDistance.default_value(), [0.0805, 0.075]);
+    assert_eq!(Gender.default_value(), "Unknown");
+    assert_eq!(StereoGuideColor.key().to_bytes(), b"DebugHudStereoGuideColor4f");
+    assert_eq!(PerfHud.default_value(), PerfHudMode::Off);
+
+    // Nul characters are rejected before reaching the runtime.
+    let err = c_string(Name.key(), "a\0b").unwrap_err();
+    assert_eq!(err.result(), ovrError_InvalidParameter);
+    assert_eq!(err.message(), "value of `Name` contains a nul character");
+}
@@ -0,0 +1,906 @@
+use ::std::collections::HashMap;
+use ::std::error;
+use ::std::fmt;
+use ::std::fs;
+use ::std::io;
+use ::std::mem;
+use ::std::path::Path;
+use ::std::time::Instant;
+
+use ::{
+    ovrBoundaryTestResult,
+    ovrControllerType,
+    ovrError_InvalidOperation,
+    ovrInputState,
+    ovrPoseStatef,
+    ovrPosef,
+    ovrSessionStatus,
+    ovrTrackingState,
+    ovrVector2f,
+    ovrVector3f
+};
+use ::boundary::BoundaryType;
+use ::error::{
+    Error,
+    Result
+};
+use ::tracking::{
+    TrackedDevice,
+    TrackingOrigin,
+    TrackingSource
+};
+
+/// The version of the replay format written by [`Recording::to_bytes`](struct.Recording.html#method.to_bytes).
+pub const REPLAY_FORMAT_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"OVRR";
+
+/// A value returned by one of the [`TrackingSource`](../tracking/trait.TrackingSource.html) queries.
+///
+/// Device poses are stored per device, so that they can be served for any set of devices.
+#[derive(Debug, Clone)]
+pub enum Sample {
+    TrackingState(ovrTrackingState),
+    TrackingOrigin(TrackingOrigin),
+    DevicePose(TrackedDevice, ovrPoseStatef),
+    InputState(ovrControllerType, ovrInputState),
+    SessionStatus(ovrSessionStatus),
+    BoundaryGeometry(BoundaryType, Option<Vec<ovrVector3f>>),
+    BoundaryTest(TrackedDevice, BoundaryType, Option<ovrBoundaryTestResult>),
+}
+
+/// Identifies the query a sample answers, so that the player can serve the latest one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    TrackingState,
+    TrackingOrigin,
+    DevicePose(TrackedDevice),
+    InputState(ovrControllerType),
+    SessionStatus,
+    BoundaryGeometry(BoundaryType),
+    BoundaryTest(TrackedDevice, BoundaryType),
+}
+
+impl Sample {
+    fn key(&self) -> Key {
+        match *self {
+            Sample::TrackingState(_) => Key::TrackingState,
+            Sample::TrackingOrigin(_) => Key::TrackingOrigin,
+            Sample::DevicePose(device, _) => Key::DevicePose(device),
+            Sample::InputState(controller_type, _) => Key::InputState(controller_type),
+            Sample::SessionStatus(_) => Key::SessionStatus,
+            Sample::BoundaryGeometry(boundary, _) => Key::BoundaryGeometry(boundary),
+            Sample::BoundaryTest(device, boundary, _) => Key::BoundaryTest(device, boundary),
+        }
+    }
+}
+
+/// A sample and the time it was recorded at, in seconds since the start of the recording.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub time: f64,
+    pub sample: Sample,
+}
+
+/// A sequence of recorded samples, with a compact binary form.
+///
+/// The binary form starts with the magic bytes `OVRR` and the format version as a `u16`, followed
+/// by the records. Each record is a tag byte, the time as an `f64` and the fields of the sample.
+/// All values are little-endian, and structs are written field by field, without padding, so that
+/// recordings can be read on any platform.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    records: Vec<Record>,
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    pub fn new() -> Recording {
+        Recording::default()
+    }
+
+    /// Adds a sample recorded at `time`.
+    pub fn push(&mut self, time: f64, sample: Sample) {
+        self.records.push(Record { time, sample });
+    }
+
+    /// The records, in the order they were added.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// The time of the last record, or 0 for an empty recording.
+    pub fn duration(&self) -> f64 {
+        self.records.iter().map(|r| r.time).fold(0.0, f64::max)
+    }
+
+    /// Reads a recording from its binary form.
+    pub fn from_bytes(data: &[u8]) -> ::std::result::Result<Recording, ReplayError> {
+        let mut reader = Reader { data };
+        if reader.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(ReplayError::NotReplay);
+        }
+        let version = reader.u16()?;
+        if version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let mut recording = Recording::new();
+        while !reader.data.is_empty() {
+            let tag = reader.u8()?;
+            let time = reader.f64()?;
+            let sample = match tag {
+                1 => Sample::TrackingState(reader.tracking_state()?),
+                2 => Sample::DevicePose(reader.device()?, reader.pose_state()?),
+                3 => Sample::InputState(reader.i32()?, reader.input_state()?),
+                4 => Sample::SessionStatus(reader.session_status()?),
+                5 => {
+                    let boundary = reader.boundary()?;
+                    let points = match reader.u32()? {
+                        u32::MAX => None,
+                        count => Some((0..count).map(|_| reader.vec3()).collect::<::std::result::Result<_, _>>()?),
+                    };
+                    Sample::BoundaryGeometry(boundary, points)
+                }
+                6 => {
+                    let device = reader.device()?;
+                    let boundary = reader.boundary()?;
+                    let result = match reader.u8()? {
+                        0 => None,
+                        _ => Some(reader.boundary_test()?),
+                    };
+                    Sample::BoundaryTest(device, boundary, result)
+                }
+                7 => Sample::TrackingOrigin(reader.origin()?),
+                tag => return Err(ReplayError::InvalidRecord(tag)),
+            };
+            recording.push(time, sample);
+        }
+        Ok(recording)
+    }
+
+    /// Writes the recording in its binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Writer { data: MAGIC.to_vec() };
+        out.u16(REPLAY_FORMAT_VERSION);
+        for record in &self.records {
+            let tag = match record.sample {
+                Sample::TrackingState(_) => 1,
+                Sample::DevicePose(..) => 2,
+                Sample::InputState(..) => 3,
+                Sample::SessionStatus(_) => 4,
+                Sample::BoundaryGeometry(..) => 5,
+                Sample::BoundaryTest(..) => 6,
+                Sample::TrackingOrigin(_) => 7,
+            };
+            out.u8(tag);
+            out.f64(record.time);
+            match record.sample {
+                Sample::TrackingState(ref state) => out.tracking_state(state),
+                Sample::TrackingOrigin(origin) => out.origin(origin),
+                Sample::DevicePose(device, ref state) => {
+                    out.device(device);
+                    out.pose_state(state);
+                }
+                Sample::InputState(controller_type, ref state) => {
+                    out.i32(controller_type);
+                    out.input_state(state);
+                }
+                Sample::SessionStatus(ref status) => out.session_status(status),
+                Sample::BoundaryGeometry(boundary, ref points) => {
+                    out.boundary(boundary);
+                    match *points {
+                        Some(ref points) => {
+                            out.u32(points.len() as u32);
+                            for point in points {
+                                out.vec3(point);
+                            }
+                        }
+                        None => out.u32(u32::MAX),
+                    }
+                }
+                Sample::BoundaryTest(device, boundary, ref result) => {
+                    out.device(device);
+                    out.boundary(boundary);
+                    match *result {
+                        Some(ref result) => {
+                            out.u8(1);
+                            out.boundary_test(result);
+                        }
+                        None => out.u8(0),
+                    }
+                }
+            }
+        }
+        out.data
+    }
+
+    /// Reads a recording from a file.
+    ///
+    /// Format errors are reported as `io::ErrorKind::InvalidData`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Recording> {
+        Recording::from_bytes(&fs::read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the recording to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+}
+
+/// A [`TrackingSource`](../tracking/trait.TrackingSource.html) that records the values returned
+/// by another source.
+///
+/// Samples are timestamped with the time elapsed since the recorder was created. Failed queries
+/// are passed through and not recorded.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::replay::Recorder;
+/// # use ovr_sys::tracking::{TrackedDevice, TrackingSource};
+/// # fn foo(session: Session, playing: bool) -> ovr_sys::error::Result<()> {
+/// let mut recorder = Recorder::new(session);
+/// while playing {
+///     let state = recorder.get_tracking_state(0.0, false)?;
+///     let poses = recorder.get_device_poses(&TrackedDevice::ALL, 0.0)?;
+///     // ... run the frame ...
+/// }
+/// recorder.recording().save("session.ovrr").unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Recorder<S> {
+    source: S,
+    recording: Recording,
+    start: Instant,
+}
+
+impl<S: TrackingSource> Recorder<S> {
+    /// Starts recording the values returned by `source`.
+    pub fn new(source: S) -> Recorder<S> {
+        Recorder {
+            source,
+            recording: Recording::new(),
+            start: Instant::now(),
+        }
+    }
+
+    /// The source being recorded.
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// The samples recorded so far.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Stops recording, returning the recorded samples.
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+
+    fn record(&mut self, sample: Sample) {
+        let elapsed = self.start.elapsed();
+        self.recording.push(elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9, sample);
+    }
+}
+
+impl<S: TrackingSource> TrackingSource for Recorder<S> {
+    fn get_tracking_state(&mut self, abs_time: f64, latency_marker: bool) -> Result<ovrTrackingState> {
+        let state = self.source.get_tracking_state(abs_time, latency_marker)?;
+        self.record(Sample::TrackingState(state));
+        Ok(state)
+    }
+
+    fn get_tracking_origin_type(&mut self) -> Result<TrackingOrigin> {
+        let origin = self.source.get_tracking_origin_type()?;
+        self.record(Sample::TrackingOrigin(origin));
+        Ok(origin)
+    }
+
+    fn get_device_poses(&mut self, devices: &[TrackedDevice], abs_time: f64) -> Result<Vec<ovrPoseStatef>> {
+        let states = self.source.get_device_poses(devices, abs_time)?;
+        for (&device, &state) in devices.iter().zip(&states) {
+            self.record(Sample::DevicePose(device, state));
+        }
+        Ok(states)
+    }
+
+    fn get_input_state(&mut self, controller_type: ovrControllerType) -> Result<ovrInputState> {
+        let state = self.source.get_input_state(controller_type)?;
+        self.record(Sample::InputState(controller_type, state));
+        Ok(state)
+    }
+
+    fn get_session_status(&mut self) -> Result<ovrSessionStatus> {
+        let status = self.source.get_session_status()?;
+        self.record(Sample::SessionStatus(status));
+        Ok(status)
+    }
+
+    fn get_boundary_geometry(&mut self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>> {
+        let points = self.source.get_boundary_geometry(boundary)?;
+        self.record(Sample::BoundaryGeometry(boundary, points.clone()));
+        Ok(points)
+    }
+
+    fn test_boundary(&mut self, device: TrackedDevice, boundary: BoundaryType) -> Result<Option<ovrBoundaryTestResult>> {
+        let result = self.source.test_boundary(device, boundary)?;
+        self.record(Sample::BoundaryTest(device, boundary, result));
+        Ok(result)
+    }
+}
+
+/// A [`TrackingSource`](../tracking/trait.TrackingSource.html) serving the values of a
+/// recording, without a headset or LibOVR.
+///
+/// Playback is driven by the caller with [`advance`](#method.advance) or [`seek`](#method.seek),
+/// so that replays are deterministic. Each query returns the latest sample recorded for the same
+/// arguments at or before the playback time, or the first one before it has been reached. The
+/// `abs_time` and `latency_marker` arguments are ignored, and values are served as they were
+/// predicted when recorded. Queries that were never recorded fail with
+/// `ovrError_InvalidOperation`.
+///
+/// ```
+/// # use ovr_sys::replay::{Player, Recording, Sample};
+/// # use ovr_sys::tracking::TrackingSource;
+/// # let status: ovr_sys::ovrSessionStatus = unsafe { ::std::mem::zeroed() };
+/// let mut recording = Recording::new();
+/// recording.push(0.5, Sample::SessionStatus(status));
+/// let bytes = recording.to_bytes();
+///
+/// let mut player = Player::new(Recording::from_bytes(&bytes).unwrap());
+/// while !player.is_finished() {
+///     let status = player.get_session_status().unwrap();
+///     // ... run the frame ...
+///     player.advance(1.0 / 90.0);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Player {
+    samples: HashMap<Key, Vec<(f64, Sample)>>,
+    duration: f64,
+    time: f64,
+}
+
+impl Player {
+    /// Starts playing a recording from its beginning.
+    pub fn new(recording: Recording) -> Player {
+        let duration = recording.duration();
+        let mut samples: HashMap<Key, Vec<(f64, Sample)>> = HashMap::new();
+        for record in recording.records {
+            samples.entry(record.sample.key()).or_default().push((record.time, record.sample));
+        }
+        for list in samples.values_mut() {
+            list.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        Player {
+            samples,
+            duration,
+            time: 0.0,
+        }
+    }
+
+    /// The playback time, in seconds since the start of the recording.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The time of the last sample.
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// Whether the playback time has passed the last sample.
+    pub fn is_finished(&self) -> bool {
+        self.time >= self.duration
+    }
+
+    /// Moves the playback time to `time`.
+    pub fn seek(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Moves the playback time forward by `seconds`.
+    pub fn advance(&mut self, seconds: f64) {
+        self.time += seconds;
+    }
+
+    fn sample(&self, key: Key) -> Result<&Sample> {
+        let list = self.samples.get(&key).map(|l| &**l).unwrap_or(&[]);
+        let index = list.partition_point(|s| s.0 <= self.time).saturating_sub(1);
+        list.get(index).map(|s| &s.1).ok_or_else(|| Error::new(ovrError_InvalidOperation, format!("no recorded sample for {:?}", key)))
+    }
+}
+
+impl TrackingSource for Player {
+    fn get_tracking_state(&mut self, _abs_time: f64, _latency_marker: bool) -> Result<ovrTrackingState> {
+        match *self.sample(Key::TrackingState)? {
+            Sample::TrackingState(state) => Ok(state),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_tracking_origin_type(&mut self) -> Result<TrackingOrigin> {
+        match *self.sample(Key::TrackingOrigin)? {
+            Sample::TrackingOrigin(origin) => Ok(origin),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_device_poses(&mut self, devices: &[TrackedDevice], _abs_time: f64) -> Result<Vec<ovrPoseStatef>> {
+        devices.iter().map(|&device| match *self.sample(Key::DevicePose(device))? {
+            Sample::DevicePose(_, state) => Ok(state),
+            _ => unreachable!(),
+        }).collect()
+    }
+
+    fn get_input_state(&mut self, controller_type: ovrControllerType) -> Result<ovrInputState> {
+        match *self.sample(Key::InputState(controller_type))? {
+            Sample::InputState(_, state) => Ok(state),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_session_status(&mut self) -> Result<ovrSessionStatus> {
+        match *self.sample(Key::SessionStatus)? {
+            Sample::SessionStatus(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_boundary_geometry(&mut self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>> {
+        match *self.sample(Key::BoundaryGeometry(boundary))? {
+            Sample::BoundaryGeometry(_, ref points) => Ok(points.clone()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn test_boundary(&mut self, device: TrackedDevice, boundary: BoundaryType) -> Result<Option<ovrBoundaryTestResult>> {
+        match *self.sample(Key::BoundaryTest(device, boundary))? {
+            Sample::BoundaryTest(_, _, result) => Ok(result),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// An error from reading the binary form of a [`Recording`](struct.Recording.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The data ended in the middle of a record.
+    Truncated,
+    /// The data does not start with the replay magic bytes.
+    NotReplay,
+    /// The data was written with a version of the format this crate cannot read.
+    UnsupportedVersion(u16),
+    /// A record has an unknown tag.
+    InvalidRecord(u8),
+    /// A record names an unknown device or boundary.
+    InvalidValue(u8),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplayError::Truncated => write!(fmt, "replay data is truncated"),
+            ReplayError::NotReplay => write!(fmt, "data is not a replay recording"),
+            ReplayError::UnsupportedVersion(version) => write!(fmt, "unsupported replay format version {}", version),
+            ReplayError::InvalidRecord(tag) => write!(fmt, "unknown replay record tag {}", tag),
+            ReplayError::InvalidValue(value) => write!(fmt, "invalid device or boundary {} in replay record", value),
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+struct Writer {
+    data: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, v: u8) {
+        self.data.push(v);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn i32(&mut self, v: i32) {
+        self.data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32s(&mut self, v: &[f32]) {
+        for &v in v {
+            self.f32(v);
+        }
+    }
+
+    fn vec2(&mut self, v: &ovrVector2f) {
+        self.f32s(&[v.x, v.y]);
+    }
+
+    fn vec3(&mut self, v: &ovrVector3f) {
+        self.f32s(&[v.x, v.y, v.z]);
+    }
+
+    fn pose(&mut self, v: &ovrPosef) {
+        self.f32s(&[v.Orientation.x, v.Orientation.y, v.Orientation.z, v.Orientation.w]);
+        self.vec3(&v.Position);
+    }
+
+    fn pose_state(&mut self, v: &ovrPoseStatef) {
+        self.pose(&v.ThePose);
+        self.vec3(&v.AngularVelocity);
+        self.vec3(&v.LinearVelocity);
+        self.vec3(&v.AngularAcceleration);
+        self.vec3(&v.LinearAcceleration);
+        self.f64(v.TimeInSeconds);
+    }
+
+    fn tracking_state(&mut self, v: &ovrTrackingState) {
+        self.pose_state(&v.HeadPose);
+        self.u32(v.StatusFlags);
+        for hand in 0..2 {
+            self.pose_state(&v.HandPoses[hand]);
+            self.u32(v.HandStatusFlags[hand]);
+        }
+        self.pose(&v.CalibratedOrigin);
+    }
+
+    fn input_state(&mut self, v: &ovrInputState) {
+        self.f64(v.TimeInSeconds);
+        self.u32(v.Buttons);
+        self.u32(v.Touches);
+        self.i32(v.ControllerType);
+        for hand in 0..2 {
+            self.f32s(&[v.IndexTrigger[hand], v.HandTrigger[hand]]);
+            self.vec2(&v.Thumbstick[hand]);
+            self.f32s(&[v.IndexTriggerNoDeadzone[hand], v.HandTriggerNoDeadzone[hand]]);
+            self.vec2(&v.ThumbstickNoDeadzone[hand]);
+            self.f32s(&[v.IndexTriggerRaw[hand], v.HandTriggerRaw[hand]]);
+            self.vec2(&v.ThumbstickRaw[hand]);
+        }
+    }
+
+    fn session_status(&mut self, v: &ovrSessionStatus) {
+        for &flag in &[v.IsVisible, v.HmdPresent, v.HmdMounted, v.DisplayLost, v.ShouldQuit, v.ShouldRecenter] {
+            self.u8(flag as u8);
+        }
+    }
+
+    fn boundary_test(&mut self, v: &ovrBoundaryTestResult) {
+        self.u8(v.IsTriggering as u8);
+        self.f32(v.ClosestDistance);
+        self.vec3(&v.ClosestPoint);
+        self.vec3(&v.ClosestPointNormal);
+    }
+
+    fn device(&mut self, device: TrackedDevice) {
+        self.u8(TrackedDevice::ALL.iter().position(|&d| d == device).unwrap() as u8);
+    }
+
+    fn boundary(&mut self, boundary: BoundaryType) {
+        self.u8(match boundary {
+            BoundaryType::Outer => 0,
+            BoundaryType::PlayArea => 1,
+        });
+    }
+
+    fn origin(&mut self, origin: TrackingOrigin) {
+        self.u8(match origin {
+            TrackingOrigin::EyeLevel => 0,
+            TrackingOrigin::FloorLevel => 1,
+        });
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+type ReadResult<T> = ::std::result::Result<T, ReplayError>;
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> ReadResult<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(ReplayError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> ReadResult<[u8; N]> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> ReadResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> ReadResult<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> ReadResult<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> ReadResult<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> ReadResult<f32> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    fn f64(&mut self) -> ReadResult<f64> {
+        self.array().map(f64::from_le_bytes)
+    }
+
+    fn f32s(&mut self, v: &mut [&mut f32]) -> ReadResult<()> {
+        for v in v.iter_mut() {
+            **v = self.f32()?;
+        }
+        Ok(())
+    }
+
+    // The raw structs are plain data, so they are zeroed before their fields are read, which
+    // leaves padding and alignment fields out of the format.
+
+    fn vec2(&mut self) -> ReadResult<ovrVector2f> {
+        let mut v: ovrVector2f = unsafe { mem::zeroed() };
+        self.f32s(&mut [&mut v.x, &mut v.y])?;
+        Ok(v)
+    }
+
+    fn vec3(&mut self) -> ReadResult<ovrVector3f> {
+        let mut v: ovrVector3f = unsafe { mem::zeroed() };
+        self.f32s(&mut [&mut v.x, &mut v.y, &mut v.z])?;
+        Ok(v)
+    }
+
+    fn pose(&mut self) -> ReadResult<ovrPosef> {
+        let mut v: ovrPosef = unsafe { mem::zeroed() };
+        {
+            let q = &mut v.Orientation;
+            self.f32s(&mut [&mut q.x, &mut q.y, &mut q.z, &mut q.w])?;
+        }
+        v.Position = self.vec3()?;
+        Ok(v)
+    }
+
+    fn pose_state(&mut self) -> ReadResult<ovrPoseStatef> {
+        let mut v: ovrPoseStatef = unsafe { mem::zeroed() };
+        v.ThePose = self.pose()?;
+        v.AngularVelocity = self.vec3()?;
+        v.LinearVelocity = self.vec3()?;
+        v.AngularAcceleration = self.vec3()?;
+        v.LinearAcceleration = self.vec3()?;
+        v.TimeInSeconds = self.f64()?;
+        Ok(v)
+    }
+
+    fn tracking_state(&mut self) -> ReadResult<ovrTrackingState> {
+        let mut v: ovrTrackingState = unsafe { mem::zeroed() };
+        v.HeadPose = self.pose_state()?;
+        v.StatusFlags = self.u32()?;
+        for hand in 0..2 {
+            v.HandPoses[hand] = self.pose_state()?;
+            v.HandStatusFlags[hand] = self.u32()?;
+        }
+        v.CalibratedOrigin = self.pose()?;
+        Ok(v)
+    }
+
+    fn input_state(&mut self) -> ReadResult<ovrInputState> {
+        let mut v: ovrInputState = unsafe { mem::zeroed() };
+        v.TimeInSeconds = self.f64()?;
+        v.Buttons = self.u32()?;
+        v.Touches = self.u32()?;
+        v.ControllerType = self.i32()?;
+        for hand in 0..2 {
+            self.f32s(&mut [&mut v.IndexTrigger[hand], &mut v.HandTrigger[hand]])?;
+            v.Thumbstick[hand] = self.vec2()?;
+            self.f32s(&mut [&mut v.IndexTriggerNoDeadzone[hand], &mut v.HandTriggerNoDeadzone[hand]])?;
+            v.ThumbstickNoDeadzone[hand] = self.vec2()?;
+            self.f32s(&mut [&mut v.IndexTriggerRaw[hand], &mut v.HandTriggerRaw[hand]])?;
+            v.ThumbstickRaw[hand] = self.vec2()?;
+        }
+        Ok(v)
+    }
+
+    fn session_status(&mut self) -> ReadResult<ovrSessionStatus> {
+        let mut v: ovrSessionStatus = unsafe { mem::zeroed() };
+        for flag in [&mut v.IsVisible, &mut v.HmdPresent, &mut v.HmdMounted, &mut v.DisplayLost, &mut v.ShouldQuit, &mut v.ShouldRecenter] {
+            *flag = self.u8()? as _;
+        }
+        Ok(v)
+    }
+
+    fn boundary_test(&mut self) -> ReadResult<ovrBoundaryTestResult> {
+        let mut v: ovrBoundaryTestResult = unsafe { mem::zeroed() };
+        v.IsTriggering = self.u8()? as _;
+        v.ClosestDistance = self.f32()?;
+        v.ClosestPoint = self.vec3()?;
+        v.ClosestPointNormal = self.vec3()?;
+        Ok(v)
+    }
+
+    fn device(&mut self) -> ReadResult<TrackedDevice> {
+        let index = self.u8()?;
+        TrackedDevice::ALL.get(index as usize).cloned().ok_or(ReplayError::InvalidValue(index))
+    }
+
+    fn boundary(&mut self) -> ReadResult<BoundaryType> {
+        match self.u8()? {
+            0 => Ok(BoundaryType::Outer),
+            1 => Ok(BoundaryType::PlayArea),
+            value => Err(ReplayError::InvalidValue(value)),
+        }
+    }
+
+    fn origin(&mut self) -> ReadResult<TrackingOrigin> {
+        match self.u8()? {
+            0 => Ok(TrackingOrigin::EyeLevel),
+            1 => Ok(TrackingOrigin::FloorLevel),
+            value => Err(ReplayError::InvalidValue(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_pose_state(x: f32, time: f64) -> ovrPoseStatef {
+    let mut state: ovrPoseStatef = unsafe { mem::zeroed() };
+    state.ThePose.Orientation.w = 1.0;
+    state.ThePose.Position = ::math::vec3(x, 1.6, -0.25);
+    state.LinearVelocity = ::math::vec3(0.5, 0.0, 0.0);
+    state.TimeInSeconds = time;
+    state
+}
+
+#[cfg(test)]
+#[test]
+fn test_record_and_replay() {
+    use ::boundary::{
+        BoundaryMonitor,
+        MonitorConfig,
+        ProximityEvent
+    };
+    use ::tracking::{
+        DevicePoses,
+        TrackingSpace
+    };
+
+    /// A scripted source standing in for a live session.
+    struct Scripted {
+        frame: u32,
+    }
+
+    impl TrackingSource for Scripted {
+        fn get_tracking_state(&mut self, _: f64, _: bool) -> Result<ovrTrackingState> {
+            self.frame += 1;
+            let mut state: ovrTrackingState = unsafe { mem::zeroed() };
+            state.HeadPose = test_pose_state(self.frame as f32, self.frame as f64 / 90.0);
+            state.StatusFlags = 3;
+            state.HandStatusFlags = [3, 0];
+            state.CalibratedOrigin.Orientation.w = 1.0;
+            state.CalibratedOrigin.Position.y = if self.frame > 2 { -1.6 } else { 0.0 };
+            Ok(state)
+        }
+
+        fn get_tracking_origin_type(&mut self) -> Result<TrackingOrigin> {
+            Ok(TrackingOrigin::FloorLevel)
+        }
+
+        fn get_device_poses(&mut self, devices: &[TrackedDevice], _: f64) -> Result<Vec<ovrPoseStatef>> {
+            Ok(devices.iter().map(|&d| test_pose_state(d.as_raw() as f32 * 10.0, 1.0)).collect())
+        }
+
+        fn get_input_state(&mut self, controller_type: ovrControllerType) -> Result<ovrInputState> {
+            let mut state: ovrInputState = unsafe { mem::zeroed() };
+            state.ControllerType = controller_type;
+            state.Buttons = ::ovrButton_A as u32;
+            state.IndexTrigger = [0.25, 0.75];
+            state.ThumbstickRaw[1] = ::math::vec2(-1.0, 0.5);
+            Ok(state)
+        }
+
+        fn get_session_status(&mut self) -> Result<ovrSessionStatus> {
+            Err(Error::new(::ovrError_ServiceError, "service unavailable"))
+        }
+
+        fn get_boundary_geometry(&mut self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>> {
+            Ok(match boundary {
+                BoundaryType::PlayArea => Some(vec![::math::vec3(-1.0, 0.0, -1.0), ::math::vec3(1.0, 0.0, -1.0), ::math::vec3(1.0, 0.0, 1.0)]),
+                BoundaryType::Outer => None,
+            })
+        }
+
+        fn test_boundary(&mut self, _: TrackedDevice, _: BoundaryType) -> Result<Option<ovrBoundaryTestResult>> {
+            let mut result: ovrBoundaryTestResult = unsafe { mem::zeroed() };
+            result.IsTriggering = ::ovrTrue;
+            result.ClosestDistance = 0.125;
+            Ok(Some(result))
+        }
+    }
+
+    let mut recorder = Recorder::new(Scripted { frame: 0 });
+    assert_eq!(TrackingSpace::from_source(&mut recorder).unwrap().origin_type(), TrackingOrigin::FloorLevel);
+    for _ in 0..2 {
+        recorder.get_tracking_state(0.0, false).unwrap();
+    }
+    recorder.get_device_poses(&[TrackedDevice::Hmd, TrackedDevice::RTouch], 0.0).unwrap();
+    recorder.get_input_state(::ovrControllerType_Touch).unwrap();
+    assert!(recorder.get_session_status().is_err());
+    recorder.get_boundary_geometry(BoundaryType::PlayArea).unwrap();
+    recorder.get_boundary_geometry(BoundaryType::Outer).unwrap();
+    recorder.test_boundary(TrackedDevice::LTouch, BoundaryType::Outer).unwrap();
+    let mut recording = recorder.into_recording();
+    assert_eq!(recording.records().len(), 10);
+
+    // Replace the wall clock timestamps so that playback is deterministic.
+    recording.records.iter_mut().enumerate().for_each(|(i, r)| r.time = i as f64 * 0.5);
+    let bytes = recording.to_bytes();
+    assert_eq!(&bytes[..6], b"OVRR\x01\x00");
+    let mut player = Player::new(Recording::from_bytes(&bytes).unwrap());
+    assert_eq!(player.duration(), 4.5);
+
+    let mut space = TrackingSpace::from_source(&mut player).unwrap();
+    assert_eq!(space.calibrated_origin().Position.y, 0.0);
+    assert_eq!(player.get_tracking_state(0.0, false).unwrap().HeadPose.ThePose.Position.x, 1.0);
+    player.seek(1.25);
+    assert!(!space.refresh_from(&mut player).unwrap());
+    assert_eq!(player.get_tracking_state(0.0, false).unwrap().HeadPose.ThePose.Position.x, 2.0);
+    player.advance(10.0);
+    assert!(player.is_finished());
+    let state = player.get_tracking_state(0.0, false).unwrap();
+    assert_eq!(state.HeadPose.ThePose.Position.x, 3.0);
+    assert_eq!(state.HeadPose.TimeInSeconds, 3.0 / 90.0);
+    assert_eq!(state.HandStatusFlags, [3, 0]);
+
+    let poses = player.get_device_poses(&[TrackedDevice::RTouch, TrackedDevice::Hmd], 0.0).unwrap();
+    assert_eq!(poses[0].ThePose.Position.x, TrackedDevice::RTouch.as_raw() as f32 * 10.0);
+    assert_eq!(poses[1].LinearVelocity.x, 0.5);
+    assert_eq!(player.get_device_poses(&[TrackedDevice::LTouch], 0.0).unwrap_err().result(), ovrError_InvalidOperation);
+
+    let input = player.get_input_state(::ovrControllerType_Touch).unwrap();
+    assert_eq!((input.Buttons, input.IndexTrigger, input.ThumbstickRaw[1].x), (::ovrButton_A as u32, [0.25, 0.75], -1.0));
+    assert!(player.get_input_state(::ovrControllerType_XBox).is_err());
+    assert!(player.get_session_status().is_err());
+    assert_eq!(player.get_boundary_geometry(BoundaryType::PlayArea).unwrap().unwrap().len(), 3);
+    assert!(player.get_boundary_geometry(BoundaryType::Outer).unwrap().is_none());
+    let test = player.test_boundary(TrackedDevice::LTouch, BoundaryType::Outer).unwrap().unwrap();
+    assert_eq!((test.IsTriggering, test.ClosestDistance), (::ovrTrue, 0.125));
+
+    // The helpers built on tracking sources can be driven by the player.
+    let poses = DevicePoses::query(&mut player, &[TrackedDevice::RTouch, TrackedDevice::Hmd, TrackedDevice::RTouch], 0.0).unwrap();
+    assert_eq!(poses.len(), 2);
+    assert!(poses.is_tracked(TrackedDevice::Hmd));
+    assert!(!poses.is_tracked(TrackedDevice::RTouch));
+    assert!(space.refresh_from(&mut player).unwrap());
+    assert_eq!((space.history().len(), space.calibrated_origin().Position.y), (2, -1.6));
+    let config = MonitorConfig { boundary: BoundaryType::Outer, ..MonitorConfig::default() };
+    let mut monitor = BoundaryMonitor::without_session(&[TrackedDevice::LTouch], config);
+    assert_eq!(monitor.poll_from(&mut player).unwrap()[0].kind, ProximityEvent::Enter);
+
+    assert_eq!(Recording::from_bytes(b"RIFF\x01\x00").unwrap_err(), ReplayError::NotReplay);
+    assert_eq!(Recording::from_bytes(b"OVRR\x02\x00").unwrap_err(), ReplayError::UnsupportedVersion(2));
+    assert_eq!(Recording::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(), ReplayError::Truncated);
+}
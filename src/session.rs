@@ -0,0 +1,43 @@
+use ::ovrSession;
+
+/// A non-owning handle to an `ovrSession`, used as the entry point to the safe wrappers in this crate.
+///
+/// Creating and destroying the session remains the responsibility of the application, through
+/// `ovr_Create` and `ovr_Destroy`. The handle is `Copy` so that it can be shared freely between the
+/// helpers that need it.
+///
+/// ```no_run
+/// # use ovr_sys::*;
+/// # use ovr_sys::session::Session;
+/// # unsafe {
+/// let mut raw: ovrSession = ::std::mem::zeroed();
+/// let mut luid: ovrGraphicsLuid = ::std::mem::zeroed();
+/// ovr_Create(&mut raw as *mut _, &mut luid as *mut _);
+/// let session = Session::from_raw(raw);
+/// // ... use `session` ...
+/// ovr_Destroy(session.as_raw());
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Session {
+    raw: ovrSession,
+}
+
+impl Session {
+    /// Wraps a raw session.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by a successful call to `ovr_Create`, and must not be destroyed
+    /// with `ovr_Destroy` while this handle, or any copy of it or helper created from it, is in use.
+    pub unsafe fn from_raw(raw: ovrSession) -> Session {
+        Session {
+            raw,
+        }
+    }
+
+    /// Returns the raw session for use with the unsafe bindings.
+    pub fn as_raw(&self) -> ovrSession {
+        self.raw
+    }
+}
@@ -0,0 +1,414 @@
+use ::std::cell::Cell;
+use ::std::error;
+use ::std::ffi::CStr;
+use ::std::fmt;
+use ::std::io::Write;
+use ::std::panic::{
+    self,
+    AssertUnwindSafe
+};
+use ::std::sync::{
+    Mutex,
+    RwLock
+};
+use ::std::sync::atomic::{
+    AtomicBool,
+    Ordering
+};
+use ::std::time::{
+    Duration,
+    Instant
+};
+
+use ::libc::c_char;
+
+/// A traced call to a LibOVR function.
+///
+/// Values are formatted with `{:#?}`. Pointers to C strings are shown as strings and pointers to
+/// inputs as the value they point to, read before the call. Pointers to outputs, the `*mut`
+/// pointers and those documented or named as outputs, are shown as addresses, and the values they
+/// point to after the call are listed as outputs when the call wrote them: always for functions
+/// returning nothing, after an `ovrResult` indicating success, after a count of elements written
+/// above 0, and never for functions returning anything else. Pointers to arrays show their first
+/// element only.
+///
+/// The `Display` implementation writes the text log format read by
+/// [`parse_trace`](fn.parse_trace.html):
+///
+/// ```text
+/// call ovr_GetSessionStatus 2150ns
+///   session = 0x1f3a2c40
+///   sessionStatus = 0x7ffd5e40
+///   sessionStatus <- ovrSessionStatus {
+///         IsVisible: 1,
+///         ...
+///     }
+///   -> 0
+/// ```
+///
+/// The header gives the function and the duration of the call, followed by one line per
+/// argument (`=`) and output (`<-`) and the result (`->`). Values spanning several lines continue
+/// on lines indented by four spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceCall {
+    /// The name of the function.
+    pub function: String,
+    /// The name and value of each argument, in order.
+    pub arguments: Vec<(String, String)>,
+    /// The name and value pointed to after the call of each output argument, in order.
+    pub outputs: Vec<(String, String)>,
+    /// The returned value, `()` for functions returning nothing.
+    pub result: String,
+    /// How long the call took, excluding the formatting of values.
+    pub duration: Duration,
+}
+
+impl TraceCall {
+    #[doc(hidden)]
+    pub fn argument(&mut self, name: &str, value: String) {
+        self.arguments.push((name.to_string(), value));
+    }
+
+    #[doc(hidden)]
+    pub fn output(&mut self, name: &str, value: String) {
+        self.outputs.push((name.to_string(), value));
+    }
+}
+
+fn write_value(fmt: &mut fmt::Formatter, prefix: &str, value: &str) -> fmt::Result {
+    let mut lines = value.lines();
+    writeln!(fmt, "{}{}", prefix, lines.next().unwrap_or(""))?;
+    for line in lines {
+        writeln!(fmt, "    {}", line)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TraceCall {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "call {} {}ns", self.function, self.duration.as_nanos())?;
+        for (name, value) in &self.arguments {
+            write_value(fmt, &format!("  {} = ", name), value)?;
+        }
+        for (name, value) in &self.outputs {
+            write_value(fmt, &format!("  {} <- ", name), value)?;
+        }
+        write_value(fmt, "  -> ", &self.result)
+    }
+}
+
+/// Receives the calls traced with the `trace` feature.
+///
+/// Sinks are called on the thread making the call. LibOVR calls made by a sink are not traced,
+/// and panics in a sink are caught and the call is dropped.
+pub trait TraceSink: Send + Sync {
+    fn record(&self, call: &TraceCall);
+}
+
+impl<F: Fn(&TraceCall) + Send + Sync> TraceSink for F {
+    fn record(&self, call: &TraceCall) {
+        self(call)
+    }
+}
+
+/// A sink writing calls in the text log format, separated by blank lines.
+///
+/// Write errors are ignored.
+///
+/// ```no_run
+/// # use ovr_sys::trace::{set_trace_sink, WriterSink};
+/// let file = ::std::fs::File::create("libovr.trace").unwrap();
+/// set_trace_sink(WriterSink::new(file));
+/// ```
+#[derive(Debug)]
+pub struct WriterSink<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    pub fn new(writer: W) -> WriterSink<W> {
+        WriterSink {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<W: Write + Send> TraceSink for WriterSink<W> {
+    fn record(&self, call: &TraceCall) {
+        let mut writer = self.writer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = writeln!(writer, "{}", call).and_then(|_| writer.flush());
+    }
+}
+
+static SINK: RwLock<Option<Box<dyn TraceSink>>> = RwLock::new(None);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static IN_SINK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sends every following LibOVR call to `sink`, replacing the previous sink.
+///
+/// Calls to the functions of the `directx` module are not traced.
+pub fn set_trace_sink<S: TraceSink + 'static>(sink: S) {
+    *SINK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Box::new(sink));
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Stops tracing calls. Calls cost one atomic load more than without the `trace` feature while no
+/// sink is set.
+pub fn clear_trace_sink() {
+    ENABLED.store(false, Ordering::Release);
+    *SINK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Runs `f`, the LibOVR call made by a generated wrapper, reporting it to the sink if one is set.
+#[doc(hidden)]
+pub fn call<R, A, F, O>(function: &str, arguments: A, f: F, outputs: O) -> R
+    where A: FnOnce(&mut TraceCall),
+          F: FnOnce() -> R,
+          O: FnOnce(&mut TraceCall, &R)
+{
+    if !ENABLED.load(Ordering::Acquire) || IN_SINK.with(Cell::get) {
+        return f();
+    }
+    let mut call = TraceCall {
+        function: function.to_string(),
+        arguments: Vec::new(),
+        outputs: Vec::new(),
+        result: String::new(),
+        duration: Duration::new(0, 0),
+    };
+    arguments(&mut call);
+    let start = Instant::now();
+    let result = f();
+    call.duration = start.elapsed();
+    outputs(&mut call, &result);
+    let _guard = SinkGuard::enter();
+    if let Some(ref sink) = *SINK.read().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+        // The wrappers are `extern "C"`, which a panicking sink must not unwind out of.
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| sink.record(&call)));
+    }
+    result
+}
+
+/// Marks the current thread as running the sink, until dropped.
+struct SinkGuard;
+
+impl SinkGuard {
+    fn enter() -> SinkGuard {
+        IN_SINK.with(|in_sink| in_sink.set(true));
+        SinkGuard
+    }
+}
+
+impl Drop for SinkGuard {
+    fn drop(&mut self) {
+        IN_SINK.with(|in_sink| in_sink.set(false));
+    }
+}
+
+#[doc(hidden)]
+pub fn value<T: fmt::Debug + ?Sized>(value: &T) -> String {
+    format!("{:#?}", value)
+}
+
+#[doc(hidden)]
+pub unsafe fn pointee<T: fmt::Debug>(pointer: *const T) -> String {
+    if pointer.is_null() {
+        "null".to_string()
+    } else {
+        value(&*pointer)
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn c_str(pointer: *const c_char) -> String {
+    if pointer.is_null() {
+        "null".to_string()
+    } else {
+        value(&*CStr::from_ptr(pointer).to_string_lossy())
+    }
+}
+
+/// An error in a text log passed to [`parse_trace`](fn.parse_trace.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTraceError {
+    line: usize,
+    message: String,
+}
+
+impl ParseTraceError {
+    fn new<S: Into<String>>(line: usize, message: S) -> ParseTraceError {
+        ParseTraceError {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// The line of the error, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseTraceError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for ParseTraceError {}
+
+/// Which value of a call a line of the text log continues.
+#[derive(Copy, Clone)]
+enum Field {
+    Argument,
+    Output,
+    Result,
+}
+
+/// Reads the calls in a text log written by [`WriterSink`](struct.WriterSink.html) or by the
+/// `Display` implementation of [`TraceCall`](struct.TraceCall.html).
+pub fn parse_trace(text: &str) -> ::std::result::Result<Vec<TraceCall>, ParseTraceError> {
+    let mut calls = Vec::new();
+    let mut current: Option<(TraceCall, Option<Field>)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() {
+            calls.extend(finish(current.take(), number)?);
+        } else if let Some(header) = line.strip_prefix("call ") {
+            calls.extend(finish(current.take(), number)?);
+            let mut parts = header.split(' ');
+            let (function, duration) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(function), Some(duration), None) if !function.is_empty() => (function, duration),
+                _ => return Err(ParseTraceError::new(number, "expected `call <function> <duration>ns`")),
+            };
+            let duration = duration.strip_suffix("ns")
+                .and_then(|nanos| nanos.parse::<u64>().ok())
+                .ok_or_else(|| ParseTraceError::new(number, format!("invalid duration `{}`", duration)))?;
+            current = Some((TraceCall {
+                function: function.to_string(),
+                arguments: Vec::new(),
+                outputs: Vec::new(),
+                result: String::new(),
+                duration: Duration::from_nanos(duration),
+            }, None));
+        } else {
+            let (call, field) = match current {
+                Some((ref mut call, ref mut field)) => (call, field),
+                None => return Err(ParseTraceError::new(number, "expected a `call` line")),
+            };
+            if let Some(continuation) = line.strip_prefix("    ") {
+                let value = match *field {
+                    Some(Field::Argument) => &mut call.arguments.last_mut().unwrap().1,
+                    Some(Field::Output) => &mut call.outputs.last_mut().unwrap().1,
+                    Some(Field::Result) => &mut call.result,
+                    None => return Err(ParseTraceError::new(number, "continuation line without a value")),
+                };
+                value.push('\n');
+                value.push_str(continuation);
+            } else if let Some(result) = line.strip_prefix("  -> ") {
+                call.result = result.to_string();
+                *field = Some(Field::Result);
+            } else if let Some(line) = line.strip_prefix("  ") {
+                // Names are identifiers, so the separator follows the first space. Values may contain
+                // either separator.
+                let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+                if let Some(value) = rest.strip_prefix("= ") {
+                    call.arguments.push((name.to_string(), value.to_string()));
+                    *field = Some(Field::Argument);
+                } else if let Some(value) = rest.strip_prefix("<- ") {
+                    call.outputs.push((name.to_string(), value.to_string()));
+                    *field = Some(Field::Output);
+                } else {
+                    return Err(ParseTraceError::new(number, "expected an argument, output or result"));
+                }
+            } else {
+                return Err(ParseTraceError::new(number, "unexpected line"));
+            }
+        }
+    }
+    calls.extend(finish(current, text.lines().count() + 1)?);
+    Ok(calls)
+}
+
+fn finish(current: Option<(TraceCall, Option<Field>)>, line: usize) -> ::std::result::Result<Option<TraceCall>, ParseTraceError> {
+    match current {
+        Some((ref call, Some(Field::Result))) if !call.result.is_empty() => Ok(current.map(|(call, _)| call)),
+        Some((ref call, _)) => Err(ParseTraceError::new(line, format!("call to {} has no result", call.function))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_trace_round_trip() {
+    use ::std::sync::Arc;
+
+    // The fields are only read through `Debug`.
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Desc {
+        width: i32,
+        name: &'static str,
+    }
+
+    let sink = Arc::new(WriterSink::new(Vec::new()));
+    {
+        let sink = sink.clone();
+        set_trace_sink(move |call: &TraceCall| sink.record(call));
+    }
+    let desc = Desc { width: 1024, name: "eye\nbuffer" };
+    let mut out = 0u32;
+    let out = &mut out as *mut u32;
+    let result = call("ovr_CreateThing", |call| {
+        call.argument("desc", unsafe { pointee(&desc as *const Desc) });
+        call.argument("label", unsafe { c_str(b"left\0".as_ptr() as *const c_char) });
+        call.argument("out", value(&out.is_null()));
+    }, || {
+        unsafe { *out = 7 };
+        0
+    }, |call, result| {
+        call.output("out", unsafe { pointee(out) });
+        call.result = value(result);
+    });
+    call("ovr_Shutdown", |_| {}, || (), |call, result| call.result = value(result));
+    clear_trace_sink();
+    call("ovr_Untraced", |_| {}, || (), |_, _| panic!("traced without a sink"));
+    set_trace_sink(|_: &TraceCall| panic!("sink failure"));
+    assert_eq!(call("ovr_Panicking", |_| {}, || 3, |call, result| call.result = value(result)), 3);
+    clear_trace_sink();
+    assert_eq!(result, 0);
+
+    let log = String::from_utf8(Arc::try_unwrap(sink).ok().unwrap().into_inner()).unwrap();
+    let calls = parse_trace(&log).unwrap();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].function, "ovr_CreateThing");
+    assert_eq!(calls[0].arguments, [
+        ("desc".to_string(), "Desc {\n    width: 1024,\n    name: \"eye\\nbuffer\",\n}".to_string()),
+        ("label".to_string(), "\"left\"".to_string()),
+        ("out".to_string(), "false".to_string()),
+    ]);
+    assert_eq!(calls[0].outputs, [("out".to_string(), "7".to_string())]);
+    assert_eq!(calls[0].result, "0");
+    assert_eq!(calls[1].result, "()");
+    assert_eq!(parse_trace(&calls.iter().map(|call| format!("{}\n", call)).collect::<String>()).unwrap(), calls);
+
+    let calls = parse_trace("call ovr_Create 12ns\n  out <- a = b\n  -> 0\n").unwrap();
+    assert_eq!(calls[0].outputs, [("out".to_string(), "a = b".to_string())]);
+    assert!(calls[0].arguments.is_empty());
+
+    assert_eq!(parse_trace("call ovr_Create 12us\n  -> 0\n").unwrap_err().line(), 1);
+    assert_eq!(parse_trace("call ovr_Create 12ns\n  session = 0x0\n").unwrap_err().line(), 3);
+    assert_eq!(unsafe { pointee(::std::ptr::null::<u32>()) }, "null");
+}
@@ -0,0 +1,231 @@
+use ::std::collections::BTreeMap;
+use ::std::error;
+use ::std::fmt;
+use ::std::fs;
+use ::std::io;
+use ::std::path::Path;
+use ::std::str::FromStr;
+
+use ::{
+    ovrPosef,
+    ovrQuatf
+};
+use ::math::{
+    pose_inverse,
+    pose_mul,
+    vec3
+};
+
+/// A named collection of poses stored relative to the calibrated origin, so that they stay in
+/// place across recenters, origin type changes and sessions.
+///
+/// Anchors are placed and resolved with the current `ovrTrackingState::CalibratedOrigin`, as
+/// tracked by [`TrackingSpace::calibrated_origin`](struct.TrackingSpace.html#method.calibrated_origin).
+///
+/// The text form holds one anchor per line as `name = px py pz qx qy qz qw`, the position followed
+/// by the orientation quaternion in the calibrated space. Blank lines and lines starting with `#`
+/// are ignored.
+///
+/// ```text
+/// # Training room props
+/// table = 0.5 0 -1.2 0 0.38268343 0 0.9238795
+/// door = -2 0 0.25 0 0 0 1
+/// ```
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::tracking::{AnchorStore, TrackingSpace};
+/// # fn foo(session: Session, prop: ovr_sys::ovrPosef) -> Result<(), Box<::std::error::Error>> {
+/// let space = TrackingSpace::new(session)?;
+/// let mut anchors = AnchorStore::load("anchors.txt").unwrap_or_default();
+/// anchors.place("table", &prop, &space.calibrated_origin());
+/// anchors.save("anchors.txt")?;
+///
+/// // Later, possibly in another session.
+/// let table = anchors.resolve("table", &space.calibrated_origin());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnchorStore {
+    anchors: BTreeMap<String, ovrPosef>,
+}
+
+impl AnchorStore {
+    /// Creates an empty store.
+    pub fn new() -> AnchorStore {
+        AnchorStore::default()
+    }
+
+    /// Reads a store from a file in the text form.
+    ///
+    /// Parse errors are reported as `io::ErrorKind::InvalidData`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<AnchorStore> {
+        fs::read_to_string(path)?.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the store to a file in the text form.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Places an anchor at `pose` in the current tracking space, returning the previous anchor with
+    /// the same name in the calibrated space.
+    ///
+    /// `calibrated_origin` is the current `ovrTrackingState::CalibratedOrigin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is empty, starts with `#` or contains whitespace or `=`, since it could
+    /// not be read back from the text form.
+    pub fn place<S: Into<String>>(&mut self, name: S, pose: &ovrPosef, calibrated_origin: &ovrPosef) -> Option<ovrPosef> {
+        self.insert(name, pose_mul(&pose_inverse(calibrated_origin), pose))
+    }
+
+    /// Resolves an anchor into the current tracking space, given the current
+    /// `ovrTrackingState::CalibratedOrigin`.
+    pub fn resolve(&self, name: &str, calibrated_origin: &ovrPosef) -> Option<ovrPosef> {
+        self.get(name).map(|pose| pose_mul(calibrated_origin, pose))
+    }
+
+    /// Looks up an anchor by name, in the calibrated space.
+    pub fn get(&self, name: &str) -> Option<&ovrPosef> {
+        self.anchors.get(name)
+    }
+
+    /// Adds or replaces an anchor in the calibrated space, returning the previous anchor with the
+    /// same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not valid, see [`place`](#method.place).
+    pub fn insert<S: Into<String>>(&mut self, name: S, pose: ovrPosef) -> Option<ovrPosef> {
+        let name = name.into();
+        assert!(valid_name(&name), "invalid anchor name `{}`", name);
+        self.anchors.insert(name, pose)
+    }
+
+    /// Removes an anchor by name.
+    pub fn remove(&mut self, name: &str) -> Option<ovrPosef> {
+        self.anchors.remove(name)
+    }
+
+    /// Iterates over the anchors in name order, in the calibrated space.
+    pub fn iter(&self) -> ::std::collections::btree_map::Iter<'_, String, ovrPosef> {
+        self.anchors.iter()
+    }
+
+    /// The number of anchors.
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    /// Whether there are no anchors.
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('#') && !name.contains(|c: char| c.is_whitespace() || c == '=')
+}
+
+impl fmt::Display for AnchorStore {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for (name, pose) in &self.anchors {
+            let (p, q) = (pose.Position, pose.Orientation);
+            writeln!(fmt, "{} = {} {} {} {} {} {} {}", name, p.x, p.y, p.z, q.x, q.y, q.z, q.w)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing the text form of an [`AnchorStore`](struct.AnchorStore.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAnchorError {
+    message: String,
+}
+
+impl ParseAnchorError {
+    fn new<S: Into<String>>(message: S) -> ParseAnchorError {
+        ParseAnchorError { message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseAnchorError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&self.message)
+    }
+}
+
+impl error::Error for ParseAnchorError {}
+
+impl FromStr for AnchorStore {
+    type Err = ParseAnchorError;
+
+    fn from_str(s: &str) -> Result<AnchorStore, ParseAnchorError> {
+        let mut store = AnchorStore::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            let pose = parts.next().unwrap_or("").trim();
+            if !valid_name(name) {
+                return Err(ParseAnchorError::new(format!("line {}: expected `name = px py pz qx qy qz qw`", number + 1)));
+            }
+            let values = pose.split_whitespace()
+                .map(|v| v.parse::<f32>().map_err(|_| ParseAnchorError::new(format!("line {}: invalid number `{}`", number + 1, v))))
+                .collect::<Result<Vec<f32>, ParseAnchorError>>()?;
+            if values.len() != 7 {
+                return Err(ParseAnchorError::new(format!("line {}: expected 7 numbers, found {}", number + 1, values.len())));
+            }
+            store.insert(name, ovrPosef {
+                _align: [],
+                Orientation: ovrQuatf {
+                    _align: [],
+                    x: values[3],
+                    y: values[4],
+                    z: values[5],
+                    w: values[6],
+                },
+                Position: vec3(values[0], values[1], values[2]),
+            });
+        }
+        Ok(store)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_anchor_store() {
+    let pose = |yaw: f32, x: f32, y: f32, z: f32| ovrPosef {
+        _align: [],
+        Orientation: ovrQuatf { _align: [], x: 0.0, y: (yaw / 2.0).sin(), z: 0.0, w: (yaw / 2.0).cos() },
+        Position: vec3(x, y, z),
+    };
+    let mut store = AnchorStore::new();
+    // Placed while the eye level origin was 1.6m above the calibrated floor origin.
+    let origin = pose(0.0, 0.0, -1.6, 0.0);
+    store.place("table", &pose(0.3, 0.5, -1.6, -1.2), &origin);
+    store.insert("door", pose(0.0, -2.0, 0.0, 0.25));
+    assert_eq!(store.get("table").unwrap().Position.y, 0.0);
+
+    // Round trip through the text form is exact.
+    let text = store.to_string();
+    let loaded: AnchorStore = format!("# props\n\n{}", text).parse().unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.to_string(), text);
+
+    // Resolved after recentering one meter to the right while facing left.
+    let recentered = pose_inverse(&pose(90.0f32.to_radians(), 1.0, 0.0, 0.0));
+    let door = loaded.resolve("door", &recentered).unwrap();
+    assert!((door.Position.x + 0.25).abs() < 1e-5 && (door.Position.z + 3.0).abs() < 1e-5);
+    assert!(loaded.resolve("window", &recentered).is_none());
+
+    assert!("table = 1 2 3".parse::<AnchorStore>().is_err());
+    assert!("table = 1 2 3 0 0 0 x".parse::<AnchorStore>().is_err());
+    assert!("my table = 1 2 3 0 0 0 1".parse::<AnchorStore>().is_err());
+}
@@ -0,0 +1,249 @@
+use ::{
+    ovrPosef,
+    ovrTrackerDesc,
+    ovrVector3f
+};
+use ::boundary::BoundaryPolygon;
+use ::math::{
+    inverse_transform,
+    transform,
+    vec3
+};
+use ::session::Session;
+use super::Tracker;
+
+/// The volume a tracking sensor can see, a pyramid frustum looking down the sensor's -Z axis.
+#[derive(Debug, Copy, Clone)]
+pub struct TrackerFrustum {
+    /// Pose of the sensor.
+    pub pose: ovrPosef,
+    /// Horizontal field of view in radians.
+    pub h_fov: f32,
+    /// Vertical field of view in radians.
+    pub v_fov: f32,
+    /// Distance to the near plane in meters.
+    pub near: f32,
+    /// Distance to the far plane in meters.
+    pub far: f32,
+}
+
+impl TrackerFrustum {
+    /// Indices into [`corners`](#method.corners) for the 12 outward facing triangles of the frustum,
+    /// wound counter-clockwise when seen from outside.
+    pub const TRIANGLES: [u32; 36] = [
+        0, 1, 2, 0, 2, 3, // near
+        4, 6, 5, 4, 7, 6, // far
+        0, 5, 1, 0, 4, 5, // bottom
+        3, 2, 6, 3, 6, 7, // top
+        0, 3, 7, 0, 7, 4, // left
+        1, 6, 2, 1, 5, 6, // right
+    ];
+
+    /// Indices into [`corners`](#method.corners) for the 12 edges of the frustum, as a line list.
+    pub const LINES: [u32; 24] = [
+        0, 1, 1, 2, 2, 3, 3, 0,
+        4, 5, 5, 6, 6, 7, 7, 4,
+        0, 4, 1, 5, 2, 6, 3, 7,
+    ];
+
+    /// Creates the frustum described by `desc` for a sensor at `pose`.
+    pub fn new(desc: &ovrTrackerDesc, pose: ovrPosef) -> TrackerFrustum {
+        TrackerFrustum {
+            pose,
+            h_fov: desc.FrustumHFovInRadians,
+            v_fov: desc.FrustumVFovInRadians,
+            near: desc.FrustumNearZInMeters,
+            far: desc.FrustumFarZInMeters,
+        }
+    }
+
+    /// Creates the frustum of a tracker at its leveled pose.
+    ///
+    /// The leveled pose drops the pitch and roll of the sensor. Pass `tracker.pose.Pose` to
+    /// [`new`](#method.new) instead for the frustum as tilted by the sensor's mount.
+    pub fn from_tracker(tracker: &Tracker) -> TrackerFrustum {
+        TrackerFrustum::new(&tracker.desc, tracker.pose.LeveledPose)
+    }
+
+    /// The corners of the frustum, counter-clockwise from the bottom left as seen by the sensor,
+    /// first on the near plane and then on the far plane.
+    pub fn corners(&self) -> [ovrVector3f; 8] {
+        let (tan_h, tan_v) = ((self.h_fov / 2.0).tan(), (self.v_fov / 2.0).tan());
+        let corner = |i: usize| {
+            let depth = if i < 4 { self.near } else { self.far };
+            let x = if i % 4 == 1 || i % 4 == 2 { 1.0 } else { -1.0 };
+            let y = if i % 4 >= 2 { 1.0 } else { -1.0 };
+            transform(&self.pose, vec3(x * depth * tan_h, y * depth * tan_v, -depth))
+        };
+        [corner(0), corner(1), corner(2), corner(3), corner(4), corner(5), corner(6), corner(7)]
+    }
+
+    /// Whether the sensor can see a point.
+    pub fn contains(&self, point: ovrVector3f) -> bool {
+        let local = inverse_transform(&self.pose, point);
+        let depth = -local.z;
+        depth >= self.near && depth <= self.far
+            && local.x.abs() <= depth * (self.h_fov / 2.0).tan()
+            && local.y.abs() <= depth * (self.v_fov / 2.0).tan()
+    }
+}
+
+/// Counts the sensors that can see a point.
+pub fn visible_count(frustums: &[TrackerFrustum], point: ovrVector3f) -> usize {
+    frustums.iter().filter(|f| f.contains(point)).count()
+}
+
+/// A point sampled by a [`Coverage`](struct.Coverage.html) map.
+#[derive(Debug, Copy, Clone)]
+pub struct CoverageSample {
+    /// The sampled point.
+    pub position: ovrVector3f,
+    /// How many sensors can see the point.
+    pub sensors: usize,
+}
+
+/// Sensor coverage sampled on a horizontal grid over a play area.
+///
+/// ```
+/// # use ovr_sys::boundary::BoundaryPolygon;
+/// # use ovr_sys::tracking::{Coverage, TrackerFrustum};
+/// # use ovr_sys::{ovrPosef, ovrQuatf, ovrVector3f};
+/// let point = |x, z| ovrVector3f { _align: [], x, y: 0.0, z };
+/// let play_area = BoundaryPolygon::new(vec![point(-1.0, -1.0), point(-1.0, 1.0), point(1.0, 1.0), point(1.0, -1.0)]);
+/// // A single sensor three meters from the center of the play area, looking at it.
+/// let sensor = TrackerFrustum {
+///     pose: ovrPosef {
+///         _align: [],
+///         Orientation: ovrQuatf { _align: [], x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+///         Position: ovrVector3f { _align: [], x: 0.0, y: 1.0, z: 3.0 },
+///     },
+///     h_fov: 1.8, v_fov: 1.0, near: 0.4, far: 5.0,
+/// };
+/// let coverage = Coverage::sample(&[sensor], &play_area, 0.25, 1.0);
+/// assert_eq!(coverage.covered_fraction(1), 1.0);
+/// assert_eq!(coverage.blind_spots(2).count(), coverage.samples.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    /// Distance between neighbouring samples.
+    pub spacing: f32,
+    /// The samples, row by row along X.
+    pub samples: Vec<CoverageSample>,
+}
+
+impl Coverage {
+    /// Samples the points inside a play area on a grid with `spacing` meters between points, at
+    /// `height` meters above the floor of the play area.
+    ///
+    /// Samples are centered in their grid cells, so a non-positive `spacing` gives no samples.
+    pub fn sample(frustums: &[TrackerFrustum], play_area: &BoundaryPolygon, spacing: f32, height: f32) -> Coverage {
+        let mut samples = Vec::new();
+        let points = play_area.points();
+        if spacing > 0.0 && !points.is_empty() {
+            let (mut min_x, mut max_x, mut min_z, mut max_z) = (points[0].x, points[0].x, points[0].z, points[0].z);
+            for p in points {
+                min_x = min_x.min(p.x);
+                max_x = max_x.max(p.x);
+                min_z = min_z.min(p.z);
+                max_z = max_z.max(p.z);
+            }
+            let y = play_area.floor_height() + height;
+            for j in 0..((max_z - min_z) / spacing).ceil() as usize {
+                for i in 0..((max_x - min_x) / spacing).ceil() as usize {
+                    let position = vec3(min_x + (i as f32 + 0.5) * spacing, y, min_z + (j as f32 + 0.5) * spacing);
+                    if play_area.contains(position) {
+                        samples.push(CoverageSample {
+                            position,
+                            sensors: visible_count(frustums, position),
+                        });
+                    }
+                }
+            }
+        }
+        Coverage {
+            spacing,
+            samples,
+        }
+    }
+
+    /// The samples seen by fewer than `min_sensors` sensors.
+    pub fn blind_spots(&self, min_sensors: usize) -> impl Iterator<Item = &CoverageSample> {
+        self.samples.iter().filter(move |s| s.sensors < min_sensors)
+    }
+
+    /// The fraction of samples seen by at least `min_sensors` sensors, or 1 if there are no samples.
+    pub fn covered_fraction(&self, min_sensors: usize) -> f32 {
+        if self.samples.is_empty() {
+            return 1.0;
+        }
+        let covered = self.samples.iter().filter(|s| s.sensors >= min_sensors).count();
+        covered as f32 / self.samples.len() as f32
+    }
+}
+
+impl Session {
+    /// The frustums of the connected sensors that have a pose, at their leveled poses.
+    pub fn tracker_frustums(&self) -> Vec<TrackerFrustum> {
+        self.trackers()
+            .filter(|t| t.flags.connected && t.flags.pose_tracked)
+            .map(|t| TrackerFrustum::from_tracker(&t))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+fn test_frustum(yaw: f32) -> TrackerFrustum {
+    TrackerFrustum {
+        pose: ovrPosef {
+            _align: [],
+            Orientation: ::ovrQuatf { _align: [], x: 0.0, y: (yaw / 2.0).sin(), z: 0.0, w: (yaw / 2.0).cos() },
+            Position: vec3(1.0, 2.0, 3.0),
+        },
+        h_fov: 90.0f32.to_radians(),
+        v_fov: 60.0f32.to_radians(),
+        near: 0.5,
+        far: 4.0,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_frustum_geometry() {
+    use ::math::{cross3, sub3};
+
+    // Turned to look down +X.
+    let frustum = test_frustum(-90.0f32.to_radians());
+    assert!(frustum.contains(vec3(3.0, 2.0, 3.0)));
+    assert!(frustum.contains(vec3(3.0, 2.5, 4.5)));
+    assert!(!frustum.contains(vec3(3.0, 2.0, 5.5)));
+    assert!(!frustum.contains(vec3(1.2, 2.0, 3.0)));
+    assert!(!frustum.contains(vec3(-1.0, 2.0, 3.0)));
+    assert!(!frustum.contains(vec3(5.5, 2.0, 3.0)));
+
+    let corners = frustum.corners();
+    assert!((corners[4].x - 5.0).abs() < 1e-5 && (corners[4].z + 1.0).abs() < 1e-5);
+    let center = corners.iter().fold(vec3(0.0, 0.0, 0.0), |c, p| vec3(c.x + p.x / 8.0, c.y + p.y / 8.0, c.z + p.z / 8.0));
+    for triangle in TrackerFrustum::TRIANGLES.chunks(3) {
+        let (a, b, c) = (corners[triangle[0] as usize], corners[triangle[1] as usize], corners[triangle[2] as usize]);
+        let normal = cross3(sub3(b, a), sub3(c, a));
+        let outward = sub3(a, center);
+        assert!(normal.x * outward.x + normal.y * outward.y + normal.z * outward.z > 0.0);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_coverage() {
+    let point = |x, z| vec3(x, 0.0, z);
+    let room = BoundaryPolygon::new(vec![point(2.0, 2.0), point(2.0, 4.0), point(4.0, 4.0), point(4.0, 2.0)]);
+    // Two sensors looking down +X from the same spot, one of them turned slightly away.
+    let frustums = [test_frustum(-90.0f32.to_radians()), test_frustum(-120.0f32.to_radians())];
+    let coverage = Coverage::sample(&frustums, &room, 0.5, 2.0);
+    assert_eq!(coverage.samples.len(), 16);
+    assert!(coverage.samples.iter().all(|s| s.position.y == 2.0));
+    assert_eq!(coverage.covered_fraction(1), 1.0);
+    let partial = coverage.covered_fraction(2);
+    assert!(partial > 0.0 && partial < 1.0);
+    assert!(coverage.blind_spots(2).all(|s| visible_count(&frustums, s.position) == 1 && s.position.z < 3.0));
+    assert!(Coverage::sample(&frustums, &room, 0.0, 1.0).samples.is_empty());
+}
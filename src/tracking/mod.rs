@@ -0,0 +1,259 @@
+use ::libc::c_uint;
+
+use ::{
+    ovrHand_Left,
+    ovrHand_Right,
+    ovrPoseStatef,
+    ovrStatus_OrientationTracked,
+    ovrStatus_PositionTracked,
+    ovrTrackedDeviceType,
+    ovrTrackedDevice_HMD,
+    ovrTrackedDevice_LTouch,
+    ovrTrackedDevice_RTouch,
+    ovrTrackingState
+};
+use ::error::Result;
+use ::session::Session;
+
+mod anchors;
+mod coverage;
+mod origin;
+mod source;
+mod trackers;
+
+pub use self::anchors::{
+    AnchorStore,
+    ParseAnchorError
+};
+pub use self::coverage::{
+    visible_count,
+    Coverage,
+    CoverageSample,
+    TrackerFrustum
+};
+pub use self::origin::{
+    convert_pose,
+    OriginChange,
+    OriginRecord,
+    TrackingOrigin,
+    TrackingSpace
+};
+pub use self::source::TrackingSource;
+pub use self::trackers::{
+    Tracker,
+    TrackerEvent,
+    TrackerFlags,
+    TrackerMonitor,
+    Trackers
+};
+
+/// Devices whose position is tracked by the sensors.
+///
+/// LibOVR 1.15 only defines the HMD and Touch device types. The Remote and Object device types
+/// of later runtimes are not available through this version of the API.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrackedDevice {
+    Hmd,
+    LTouch,
+    RTouch,
+}
+
+impl TrackedDevice {
+    /// Every tracked device, in the order of their `ovrTrackedDeviceType` bits.
+    pub const ALL: [TrackedDevice; 3] = [TrackedDevice::Hmd, TrackedDevice::LTouch, TrackedDevice::RTouch];
+
+    /// The corresponding `ovrTrackedDeviceType`.
+    pub fn as_raw(&self) -> ovrTrackedDeviceType {
+        match *self {
+            TrackedDevice::Hmd => ovrTrackedDevice_HMD,
+            TrackedDevice::LTouch => ovrTrackedDevice_LTouch,
+            TrackedDevice::RTouch => ovrTrackedDevice_RTouch,
+        }
+    }
+
+    /// The device for a single `ovrTrackedDeviceType`, or `None` for masks and unknown values.
+    pub fn from_raw(raw: ovrTrackedDeviceType) -> Option<TrackedDevice> {
+        TrackedDevice::ALL.iter().cloned().find(|d| d.as_raw() == raw)
+    }
+
+    /// The status flags for this device in a tracking state.
+    fn status_flags(&self, state: &ovrTrackingState) -> c_uint {
+        match *self {
+            TrackedDevice::Hmd => state.StatusFlags,
+            TrackedDevice::LTouch => state.HandStatusFlags[ovrHand_Left as usize],
+            TrackedDevice::RTouch => state.HandStatusFlags[ovrHand_Right as usize],
+        }
+    }
+}
+
+/// Tracking status of a device, from the `ovrStatusBits` reported by `ovr_GetTrackingState`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TrackingStatus {
+    /// Orientation is currently tracked.
+    pub orientation_tracked: bool,
+    /// Position is currently tracked.
+    pub position_tracked: bool,
+}
+
+impl TrackingStatus {
+    /// Decodes `ovrStatusBits` flags.
+    pub fn from_raw(flags: c_uint) -> TrackingStatus {
+        TrackingStatus {
+            orientation_tracked: flags & ovrStatus_OrientationTracked as c_uint != 0,
+            position_tracked: flags & ovrStatus_PositionTracked as c_uint != 0,
+        }
+    }
+
+    /// Whether the device is fully tracked, in both orientation and position.
+    pub fn is_tracked(&self) -> bool {
+        self.orientation_tracked && self.position_tracked
+    }
+}
+
+/// The pose of one tracked device.
+#[derive(Debug, Copy, Clone)]
+pub struct DevicePose {
+    /// The device.
+    pub device: TrackedDevice,
+    /// The pose and its derivatives, as returned by `ovr_GetDevicePoses`.
+    pub state: ovrPoseStatef,
+    /// Whether the device is tracked.
+    pub status: TrackingStatus,
+}
+
+/// Poses of a set of devices at one point in time, as returned by [`Session::device_poses`](../session/struct.Session.html#method.device_poses).
+#[derive(Debug, Clone)]
+pub struct DevicePoses {
+    time: f64,
+    poses: Vec<DevicePose>,
+}
+
+impl DevicePoses {
+    /// Collects poses, keeping the first pose of each device.
+    pub fn new(time: f64, poses: Vec<DevicePose>) -> DevicePoses {
+        let mut unique: Vec<DevicePose> = Vec::with_capacity(poses.len());
+        for pose in poses {
+            if unique.iter().all(|p| p.device != pose.device) {
+                unique.push(pose);
+            }
+        }
+        DevicePoses {
+            time,
+            poses: unique,
+        }
+    }
+
+    /// Gets the poses of `devices` predicted at `abs_time` from any tracking source, together with
+    /// their tracking status, as [`Session::device_poses`](../session/struct.Session.html#method.device_poses)
+    /// does for the live session.
+    ///
+    /// This allows getting the poses from a recording, see [`Player`](../replay/struct.Player.html).
+    pub fn query<S: TrackingSource + ?Sized>(source: &mut S, devices: &[TrackedDevice], abs_time: f64) -> Result<DevicePoses> {
+        let mut unique: Vec<TrackedDevice> = Vec::with_capacity(devices.len());
+        for &device in devices {
+            if !unique.contains(&device) {
+                unique.push(device);
+            }
+        }
+        let states = source.get_device_poses(&unique, abs_time)?;
+        let tracking = source.get_tracking_state(abs_time, false)?;
+        let poses = unique.into_iter().zip(states).map(|(device, state)| DevicePose {
+            device,
+            state,
+            status: TrackingStatus::from_raw(device.status_flags(&tracking)),
+        }).collect();
+        Ok(DevicePoses::new(abs_time, poses))
+    }
+
+    /// The absolute time the poses were predicted for, or 0 for the most recent poses.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The pose of a device, if it was queried.
+    pub fn get(&self, device: TrackedDevice) -> Option<&DevicePose> {
+        self.poses.iter().find(|p| p.device == device)
+    }
+
+    /// The pose of a device, if it was queried and is fully tracked.
+    pub fn tracked(&self, device: TrackedDevice) -> Option<&DevicePose> {
+        self.get(device).filter(|p| p.status.is_tracked())
+    }
+
+    /// Whether a device was queried and is fully tracked.
+    pub fn is_tracked(&self, device: TrackedDevice) -> bool {
+        self.tracked(device).is_some()
+    }
+
+    /// The poses of all queried devices, in the order they were requested.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, DevicePose> {
+        self.poses.iter()
+    }
+
+    /// The number of queried devices.
+    pub fn len(&self) -> usize {
+        self.poses.len()
+    }
+
+    /// Whether no devices were queried.
+    pub fn is_empty(&self) -> bool {
+        self.poses.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a DevicePoses {
+    type Item = &'a DevicePose;
+    type IntoIter = ::std::slice::Iter<'a, DevicePose>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.poses.iter()
+    }
+}
+
+impl Session {
+    /// Gets the poses of `devices` predicted at `abs_time` using `ovr_GetDevicePoses`, together
+    /// with their tracking status from `ovr_GetTrackingState`.
+    ///
+    /// Use an `abs_time` of 0 to get the most recent poses. Devices listed more than once are only
+    /// queried once.
+    ///
+    /// ```no_run
+    /// # use ovr_sys::session::Session;
+    /// # use ovr_sys::tracking::TrackedDevice;
+    /// # fn foo(session: Session) -> ovr_sys::error::Result<()> {
+    /// let poses = session.device_poses(&TrackedDevice::ALL, 0.0)?;
+    /// if let Some(hand) = poses.tracked(TrackedDevice::RTouch) {
+    ///     println!("right hand at {:?}", hand.state.ThePose.Position);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn device_poses(&self, devices: &[TrackedDevice], abs_time: f64) -> Result<DevicePoses> {
+        let mut session = *self;
+        DevicePoses::query(&mut session, devices, abs_time)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_device_poses() {
+    assert_eq!(TrackedDevice::from_raw(::ovrTrackedDevice_Touch), None);
+    for &device in &TrackedDevice::ALL {
+        assert_eq!(TrackedDevice::from_raw(device.as_raw()), Some(device));
+    }
+    assert!(!TrackingStatus::from_raw(ovrStatus_OrientationTracked as c_uint).is_tracked());
+    assert!(TrackingStatus::from_raw(0x3).is_tracked());
+
+    let pose = |device, flags| DevicePose {
+        device,
+        state: unsafe { ::std::mem::zeroed() },
+        status: TrackingStatus::from_raw(flags),
+    };
+    let poses = DevicePoses::new(1.5, vec![pose(TrackedDevice::Hmd, 0x3), pose(TrackedDevice::LTouch, 0x1), pose(TrackedDevice::Hmd, 0x0)]);
+    assert_eq!(poses.len(), 2);
+    assert!(poses.is_tracked(TrackedDevice::Hmd));
+    assert!(poses.get(TrackedDevice::LTouch).is_some());
+    assert!(!poses.is_tracked(TrackedDevice::LTouch));
+    assert!(poses.get(TrackedDevice::RTouch).is_none());
+    assert_eq!(poses.iter().map(|p| p.device).collect::<Vec<_>>(), [TrackedDevice::Hmd, TrackedDevice::LTouch]);
+}
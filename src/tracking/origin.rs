@@ -0,0 +1,299 @@
+use ::{
+    ovrError_InvalidOperation,
+    ovrPosef,
+    ovrTrackingOrigin,
+    ovrTrackingOrigin_EyeLevel,
+    ovrTrackingOrigin_FloorLevel,
+    ovr_RecenterTrackingOrigin,
+    ovr_SetTrackingOriginType,
+    ovr_SpecifyTrackingOrigin
+};
+use ::error::{
+    check_call,
+    Error,
+    Result
+};
+use ::math::{
+    pose_inverse,
+    pose_mul
+};
+use ::session::Session;
+
+use super::TrackingSource;
+
+/// Where the tracking origin lies vertically.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrackingOrigin {
+    /// At the height of the HMD when the origin was last recentered, `ovrTrackingOrigin_EyeLevel`.
+    EyeLevel,
+    /// On the floor, `ovrTrackingOrigin_FloorLevel`.
+    FloorLevel,
+}
+
+impl TrackingOrigin {
+    /// The corresponding `ovrTrackingOrigin`.
+    pub fn as_raw(&self) -> ovrTrackingOrigin {
+        match *self {
+            TrackingOrigin::EyeLevel => ovrTrackingOrigin_EyeLevel,
+            TrackingOrigin::FloorLevel => ovrTrackingOrigin_FloorLevel,
+        }
+    }
+
+    /// The origin for an `ovrTrackingOrigin`, or `None` for unknown values.
+    pub fn from_raw(raw: ovrTrackingOrigin) -> Option<TrackingOrigin> {
+        match raw {
+            ovrTrackingOrigin_EyeLevel => Some(TrackingOrigin::EyeLevel),
+            ovrTrackingOrigin_FloorLevel => Some(TrackingOrigin::FloorLevel),
+            _ => None,
+        }
+    }
+}
+
+/// What moved the tracking origin, as recorded by [`TrackingSpace`](struct.TrackingSpace.html).
+#[derive(Debug, Copy, Clone)]
+pub enum OriginChange {
+    /// The state when the `TrackingSpace` was created.
+    Initial,
+    /// `ovr_SetTrackingOriginType` switched to this origin type.
+    OriginType(TrackingOrigin),
+    /// `ovr_RecenterTrackingOrigin` was called.
+    Recentered,
+    /// `ovr_SpecifyTrackingOrigin` was called with this pose.
+    Specified(ovrPosef),
+    /// The origin moved without going through the `TrackingSpace`, for example because the user
+    /// recentered from the Oculus dashboard.
+    External,
+}
+
+/// The tracking origin after a change.
+#[derive(Debug, Copy, Clone)]
+pub struct OriginRecord {
+    /// What moved the origin.
+    pub change: OriginChange,
+    /// The origin type in effect after the change.
+    pub origin_type: TrackingOrigin,
+    /// `ovrTrackingState::CalibratedOrigin` after the change, the pose of the calibrated origin in
+    /// the tracking space the runtime reported poses in from then on.
+    pub calibrated_origin: ovrPosef,
+}
+
+/// Converts a pose from a tracking space whose calibrated origin was `from` into one whose
+/// calibrated origin is `to`.
+pub fn convert_pose(pose: &ovrPosef, from: &ovrPosef, to: &ovrPosef) -> ovrPosef {
+    pose_mul(to, &pose_mul(&pose_inverse(from), pose))
+}
+
+/// Keeps track of changes to the tracking origin, so that poses stored in one tracking space can
+/// be brought into another.
+///
+/// The calibrated origin set up by the user in the Oculus app does not move when the application
+/// recenters or switches between eye and floor level. The runtime reports its pose in the current
+/// tracking space as `ovrTrackingState::CalibratedOrigin`, which is enough to convert poses across
+/// any of those changes.
+///
+/// ```no_run
+/// # use ovr_sys::session::Session;
+/// # use ovr_sys::tracking::{TrackingOrigin, TrackingSpace};
+/// # fn foo(session: Session, anchor: ovr_sys::ovrPosef) -> ovr_sys::error::Result<()> {
+/// let mut space = TrackingSpace::new(session)?;
+/// let before = space.history().len() - 1;
+/// space.set_origin_type(TrackingOrigin::FloorLevel)?;
+/// space.recenter()?;
+/// // `anchor` was placed before the changes, find where it is now.
+/// let anchor = space.convert_from(&anchor, before).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackingSpace {
+    session: Option<Session>,
+    history: Vec<OriginRecord>,
+}
+
+impl TrackingSpace {
+    /// Starts tracking the origin of `session` from its current state.
+    pub fn new(mut session: Session) -> Result<TrackingSpace> {
+        let mut space = TrackingSpace::from_source(&mut session)?;
+        space.session = Some(session);
+        Ok(space)
+    }
+
+    /// Starts tracking the origin of any tracking source from its current state, for example a
+    /// recording played back by a [`Player`](../replay/struct.Player.html).
+    ///
+    /// The resulting space has no session, so it can only follow the origin through
+    /// [`refresh_from`](#method.refresh_from). The methods moving the origin fail with
+    /// `ovrError_InvalidOperation`.
+    pub fn from_source<S: TrackingSource + ?Sized>(source: &mut S) -> Result<TrackingSpace> {
+        let (origin_type, calibrated_origin) = read_from(source)?;
+        Ok(TrackingSpace {
+            session: None,
+            history: vec![OriginRecord {
+                change: OriginChange::Initial,
+                origin_type,
+                calibrated_origin,
+            }],
+        })
+    }
+
+    /// Every recorded state of the origin, oldest first. The last record is the current state.
+    pub fn history(&self) -> &[OriginRecord] {
+        &self.history
+    }
+
+    /// The current state of the origin.
+    pub fn current(&self) -> &OriginRecord {
+        self.history.last().expect("tracking space history is never empty")
+    }
+
+    /// The current origin type.
+    pub fn origin_type(&self) -> TrackingOrigin {
+        self.current().origin_type
+    }
+
+    /// The pose of the calibrated origin in the current tracking space.
+    pub fn calibrated_origin(&self) -> ovrPosef {
+        self.current().calibrated_origin
+    }
+
+    /// Switches the origin type using `ovr_SetTrackingOriginType`.
+    pub fn set_origin_type(&mut self, origin: TrackingOrigin) -> Result<()> {
+        let mut session = self.session()?;
+        check_call("ovr_SetTrackingOriginType", || format!("{:?}", origin), unsafe { ovr_SetTrackingOriginType(session.as_raw(), origin.as_raw()) })?;
+        self.record(&mut session, OriginChange::OriginType(origin))
+    }
+
+    /// Recenters the origin on the HMD using `ovr_RecenterTrackingOrigin`.
+    pub fn recenter(&mut self) -> Result<()> {
+        let mut session = self.session()?;
+        check_call("ovr_RecenterTrackingOrigin", String::new, unsafe { ovr_RecenterTrackingOrigin(session.as_raw()) })?;
+        self.record(&mut session, OriginChange::Recentered)
+    }
+
+    /// Moves the origin by `pose` using `ovr_SpecifyTrackingOrigin`.
+    pub fn specify_origin(&mut self, pose: ovrPosef) -> Result<()> {
+        let mut session = self.session()?;
+        check_call("ovr_SpecifyTrackingOrigin", || format!("{:?}", pose), unsafe { ovr_SpecifyTrackingOrigin(session.as_raw(), pose) })?;
+        self.record(&mut session, OriginChange::Specified(pose))
+    }
+
+    /// Checks whether the origin moved without going through this `TrackingSpace`, recording an
+    /// `External` change if it did. Returns whether a change was recorded.
+    ///
+    /// Call this once per frame to notice recenters requested by the user.
+    pub fn refresh(&mut self) -> Result<bool> {
+        let mut session = self.session()?;
+        self.refresh_from(&mut session)
+    }
+
+    /// Checks whether the origin reported by any tracking source moved, as `refresh` does for the
+    /// session of the space.
+    ///
+    /// This allows following the origin through a recording, see
+    /// [`Player`](../replay/struct.Player.html).
+    pub fn refresh_from<S: TrackingSource + ?Sized>(&mut self, source: &mut S) -> Result<bool> {
+        let (origin_type, calibrated_origin) = read_from(source)?;
+        Ok(self.push_if_moved(origin_type, calibrated_origin))
+    }
+
+    /// Converts a pose in the current tracking space into the calibrated space, which does not
+    /// change when the origin moves.
+    pub fn to_calibrated(&self, pose: &ovrPosef) -> ovrPosef {
+        pose_mul(&pose_inverse(&self.calibrated_origin()), pose)
+    }
+
+    /// Converts a pose in the calibrated space into the current tracking space.
+    pub fn from_calibrated(&self, pose: &ovrPosef) -> ovrPosef {
+        pose_mul(&self.calibrated_origin(), pose)
+    }
+
+    /// Converts a pose in the tracking space of the record at `index` in the
+    /// [`history`](#method.history) into the current tracking space, or `None` if there is no
+    /// such record.
+    pub fn convert_from(&self, pose: &ovrPosef, index: usize) -> Option<ovrPosef> {
+        let from = self.history.get(index)?;
+        Some(convert_pose(pose, &from.calibrated_origin, &self.calibrated_origin()))
+    }
+
+    fn session(&self) -> Result<Session> {
+        self.session.ok_or_else(|| Error::new(ovrError_InvalidOperation, "tracking space has no session"))
+    }
+
+    fn record(&mut self, session: &mut Session, change: OriginChange) -> Result<()> {
+        let (origin_type, calibrated_origin) = read_from(session)?;
+        self.history.push(OriginRecord {
+            change,
+            origin_type,
+            calibrated_origin,
+        });
+        Ok(())
+    }
+
+    fn push_if_moved(&mut self, origin_type: TrackingOrigin, calibrated_origin: ovrPosef) -> bool {
+        let current = *self.current();
+        let (a, b) = (current.calibrated_origin, calibrated_origin);
+        let moved = current.origin_type != origin_type || [
+            a.Position.x - b.Position.x, a.Position.y - b.Position.y, a.Position.z - b.Position.z,
+            a.Orientation.x - b.Orientation.x, a.Orientation.y - b.Orientation.y,
+            a.Orientation.z - b.Orientation.z, a.Orientation.w - b.Orientation.w,
+        ].iter().any(|d| d.abs() > 1e-5);
+        if moved {
+            self.history.push(OriginRecord {
+                change: OriginChange::External,
+                origin_type,
+                calibrated_origin,
+            });
+        }
+        moved
+    }
+}
+
+fn read_from<S: TrackingSource + ?Sized>(source: &mut S) -> Result<(TrackingOrigin, ovrPosef)> {
+    let origin_type = source.get_tracking_origin_type()?;
+    let state = source.get_tracking_state(0.0, false)?;
+    Ok((origin_type, state.CalibratedOrigin))
+}
+
+#[cfg(test)]
+fn test_pose(yaw: f32, x: f32, y: f32, z: f32) -> ovrPosef {
+    ovrPosef {
+        _align: [],
+        Orientation: ::ovrQuatf { _align: [], x: 0.0, y: (yaw / 2.0).sin(), z: 0.0, w: (yaw / 2.0).cos() },
+        Position: ::math::vec3(x, y, z),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_tracking_space() {
+    let mut space = TrackingSpace {
+        session: None,
+        history: vec![OriginRecord {
+            change: OriginChange::Initial,
+            origin_type: TrackingOrigin::EyeLevel,
+            calibrated_origin: test_pose(0.0, 0.0, -1.6, 0.0),
+        }],
+    };
+    // An anchor one meter in front of the eye level origin, on the floor.
+    let anchor = test_pose(0.0, 0.0, -1.6, -1.0);
+    let calibrated = space.to_calibrated(&anchor);
+    assert!((calibrated.Position.y).abs() < 1e-6 && (calibrated.Position.z + 1.0).abs() < 1e-6);
+
+    assert!(!space.push_if_moved(TrackingOrigin::EyeLevel, test_pose(0.0, 0.0, -1.6, 0.0)));
+    // Switching to floor level, then recentering one meter to the right while facing left.
+    assert!(space.push_if_moved(TrackingOrigin::FloorLevel, test_pose(0.0, 0.0, 0.0, 0.0)));
+    assert!(space.push_if_moved(TrackingOrigin::FloorLevel, pose_inverse(&test_pose(90.0f32.to_radians(), 1.0, 0.0, 0.0))));
+    assert_eq!(space.history().len(), 3);
+    assert_eq!(space.origin_type(), TrackingOrigin::FloorLevel);
+
+    let moved = space.convert_from(&anchor, 0).unwrap();
+    let resolved = space.from_calibrated(&calibrated);
+    for pose in &[moved, resolved] {
+        // The anchor is on the floor one meter ahead and one meter to the right of the new origin.
+        assert!((pose.Position.x - 1.0).abs() < 1e-5);
+        assert!(pose.Position.y.abs() < 1e-5);
+        assert!((pose.Position.z + 1.0).abs() < 1e-5);
+    }
+    assert!(space.convert_from(&anchor, 3).is_none());
+    assert_eq!(TrackingOrigin::from_raw(TrackingOrigin::FloorLevel.as_raw()), Some(TrackingOrigin::FloorLevel));
+}
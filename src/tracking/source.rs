@@ -0,0 +1,108 @@
+use ::std::mem;
+
+use ::libc::c_int;
+
+use ::{
+    ovrBoundaryTestResult,
+    ovrControllerType,
+    ovrFalse,
+    ovrInputState,
+    ovrPoseStatef,
+    ovrSessionStatus,
+    ovrSuccess,
+    ovrTrackedDeviceType,
+    ovrTrackingState,
+    ovrTrue,
+    ovrVector3f,
+    ovr_GetDevicePoses,
+    ovr_GetInputState,
+    ovr_GetSessionStatus,
+    ovr_GetTrackingOriginType,
+    ovr_GetTrackingState,
+    ovr_TestBoundary
+};
+use ::boundary::BoundaryType;
+use ::error::{
+    check_call,
+    Result
+};
+use ::session::Session;
+use super::{
+    TrackedDevice,
+    TrackingOrigin
+};
+
+/// The tracking, input, session status and boundary queries of a session, which can be recorded
+/// and replayed.
+///
+/// Each method mirrors a LibOVR function. [`Session`](../session/struct.Session.html) implements
+/// it with the live runtime, [`Recorder`](../replay/struct.Recorder.html) records the values
+/// returned by another source, and [`Player`](../replay/struct.Player.html) serves recorded values
+/// on any platform. Code written against this trait can be tested from recorded play sessions, as
+/// can [`DevicePoses::query`](struct.DevicePoses.html#method.query),
+/// [`TrackingSpace::refresh_from`](struct.TrackingSpace.html#method.refresh_from) and
+/// [`BoundaryMonitor::poll_from`](../boundary/struct.BoundaryMonitor.html#method.poll_from).
+pub trait TrackingSource {
+    /// `ovr_GetTrackingState`.
+    fn get_tracking_state(&mut self, abs_time: f64, latency_marker: bool) -> Result<ovrTrackingState>;
+
+    /// `ovr_GetTrackingOriginType`.
+    fn get_tracking_origin_type(&mut self) -> Result<TrackingOrigin>;
+
+    /// `ovr_GetDevicePoses`, returning the pose of each device in order.
+    fn get_device_poses(&mut self, devices: &[TrackedDevice], abs_time: f64) -> Result<Vec<ovrPoseStatef>>;
+
+    /// `ovr_GetInputState`.
+    fn get_input_state(&mut self, controller_type: ovrControllerType) -> Result<ovrInputState>;
+
+    /// `ovr_GetSessionStatus`.
+    fn get_session_status(&mut self) -> Result<ovrSessionStatus>;
+
+    /// The floor points of a boundary, or `None` if the Guardian is not set up, as returned by
+    /// [`Boundary::geometry`](../boundary/struct.Boundary.html#method.geometry).
+    fn get_boundary_geometry(&mut self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>>;
+
+    /// `ovr_TestBoundary`, or `None` if the boundary is not set up or the device is not available.
+    fn test_boundary(&mut self, device: TrackedDevice, boundary: BoundaryType) -> Result<Option<ovrBoundaryTestResult>>;
+}
+
+impl TrackingSource for Session {
+    fn get_tracking_state(&mut self, abs_time: f64, latency_marker: bool) -> Result<ovrTrackingState> {
+        Ok(unsafe { ovr_GetTrackingState(self.as_raw(), abs_time, if latency_marker { ovrTrue } else { ovrFalse }) })
+    }
+
+    fn get_tracking_origin_type(&mut self) -> Result<TrackingOrigin> {
+        Ok(TrackingOrigin::from_raw(unsafe { ovr_GetTrackingOriginType(self.as_raw()) }).unwrap_or(TrackingOrigin::EyeLevel))
+    }
+
+    fn get_device_poses(&mut self, devices: &[TrackedDevice], abs_time: f64) -> Result<Vec<ovrPoseStatef>> {
+        let types: Vec<ovrTrackedDeviceType> = devices.iter().map(|d| d.as_raw()).collect();
+        let mut states: Vec<ovrPoseStatef> = vec![unsafe { mem::zeroed() }; types.len()];
+        if !types.is_empty() {
+            check_call("ovr_GetDevicePoses", || format!("{:?}, {}", devices, abs_time), unsafe { ovr_GetDevicePoses(self.as_raw(), types.as_ptr(), types.len() as c_int, abs_time, states.as_mut_ptr()) })?;
+        }
+        Ok(states)
+    }
+
+    fn get_input_state(&mut self, controller_type: ovrControllerType) -> Result<ovrInputState> {
+        let mut state: ovrInputState = unsafe { mem::zeroed() };
+        check_call("ovr_GetInputState", || format!("{:#x}", controller_type), unsafe { ovr_GetInputState(self.as_raw(), controller_type, &mut state as *mut _) })?;
+        Ok(state)
+    }
+
+    fn get_session_status(&mut self) -> Result<ovrSessionStatus> {
+        let mut status: ovrSessionStatus = unsafe { mem::zeroed() };
+        check_call("ovr_GetSessionStatus", String::new, unsafe { ovr_GetSessionStatus(self.as_raw(), &mut status as *mut _) })?;
+        Ok(status)
+    }
+
+    fn get_boundary_geometry(&mut self, boundary: BoundaryType) -> Result<Option<Vec<ovrVector3f>>> {
+        self.boundary().geometry(boundary)
+    }
+
+    fn test_boundary(&mut self, device: TrackedDevice, boundary: BoundaryType) -> Result<Option<ovrBoundaryTestResult>> {
+        let mut result: ovrBoundaryTestResult = unsafe { mem::zeroed() };
+        let status = check_call("ovr_TestBoundary", || format!("{:?}, {:?}", device, boundary), unsafe { ovr_TestBoundary(self.as_raw(), device.as_raw(), boundary.as_raw(), &mut result as *mut _) })?;
+        Ok(if status == ovrSuccess { Some(result) } else { None })
+    }
+}
@@ -0,0 +1,198 @@
+use ::libc::c_uint;
+
+use ::{
+    ovrTrackerDesc,
+    ovrTrackerPose,
+    ovrTracker_Connected,
+    ovrTracker_PoseTracked,
+    ovr_GetTrackerCount,
+    ovr_GetTrackerDesc,
+    ovr_GetTrackerPose
+};
+use ::session::Session;
+
+/// Status of a tracking sensor, from the `ovrTrackerFlags` in `ovrTrackerPose::TrackerFlags`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TrackerFlags {
+    /// The sensor is present, `ovrTracker_Connected`.
+    pub connected: bool,
+    /// The sensor has a valid pose, `ovrTracker_PoseTracked`.
+    pub pose_tracked: bool,
+}
+
+impl TrackerFlags {
+    /// Decodes `ovrTrackerFlags`.
+    pub fn from_raw(flags: c_uint) -> TrackerFlags {
+        TrackerFlags {
+            connected: flags & ovrTracker_Connected as c_uint != 0,
+            pose_tracked: flags & ovrTracker_PoseTracked as c_uint != 0,
+        }
+    }
+}
+
+/// A tracking sensor, combining its description and its pose.
+#[derive(Debug, Copy, Clone)]
+pub struct Tracker {
+    /// Index of the sensor, as used by `ovr_GetTrackerDesc` and `ovr_GetTrackerPose`.
+    pub index: u32,
+    /// Frustum of the sensor.
+    pub desc: ovrTrackerDesc,
+    /// Pose of the sensor, both as is and leveled to gravity.
+    pub pose: ovrTrackerPose,
+    /// Decoded `pose.TrackerFlags`.
+    pub flags: TrackerFlags,
+}
+
+impl Tracker {
+    /// Reads the sensor at `index`, which returns empty data if the index is out of range.
+    fn read(session: Session, index: u32) -> Tracker {
+        let desc = unsafe { ovr_GetTrackerDesc(session.as_raw(), index) };
+        let pose = unsafe { ovr_GetTrackerPose(session.as_raw(), index) };
+        Tracker {
+            index,
+            desc,
+            pose,
+            flags: TrackerFlags::from_raw(pose.TrackerFlags),
+        }
+    }
+}
+
+/// Iterator over the tracking sensors attached to a session, see [`Session::trackers`](../session/struct.Session.html#method.trackers).
+///
+/// The number of sensors is read when the iterator is created.
+#[derive(Debug, Clone)]
+pub struct Trackers {
+    session: Session,
+    next: u32,
+    count: u32,
+}
+
+impl Iterator for Trackers {
+    type Item = Tracker;
+
+    fn next(&mut self) -> Option<Tracker> {
+        if self.next < self.count {
+            let tracker = Tracker::read(self.session, self.next);
+            self.next += 1;
+            Some(tracker)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.count - self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Trackers {}
+
+impl Session {
+    /// Iterates over the attached tracking sensors using `ovr_GetTrackerCount`, `ovr_GetTrackerDesc`
+    /// and `ovr_GetTrackerPose`.
+    ///
+    /// ```no_run
+    /// # use ovr_sys::session::Session;
+    /// # fn foo(session: Session) {
+    /// for tracker in session.trackers() {
+    ///     if tracker.flags.pose_tracked {
+    ///         println!("sensor {} at {:?}", tracker.index, tracker.pose.LeveledPose.Position);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn trackers(&self) -> Trackers {
+        Trackers {
+            session: *self,
+            next: 0,
+            count: unsafe { ovr_GetTrackerCount(self.as_raw()) },
+        }
+    }
+
+    /// Reads a single tracking sensor, or `None` if `index` is out of range.
+    pub fn tracker(&self, index: u32) -> Option<Tracker> {
+        if index < unsafe { ovr_GetTrackerCount(self.as_raw()) } {
+            Some(Tracker::read(*self, index))
+        } else {
+            None
+        }
+    }
+}
+
+/// A change in the status of a tracking sensor, reported by [`TrackerMonitor`](struct.TrackerMonitor.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrackerEvent {
+    /// The sensor at this index was plugged in.
+    Connected(u32),
+    /// The sensor at this index was unplugged or went offline.
+    Disconnected(u32),
+    /// The sensor at this index found its pose.
+    PoseTracked(u32),
+    /// The sensor at this index lost its pose, for example because it was knocked.
+    PoseLost(u32),
+}
+
+/// Detects sensors being plugged in, unplugged or losing their pose during a session.
+///
+/// The monitor starts with no known sensors, so the first update reports every connected sensor.
+#[derive(Debug, Clone, Default)]
+pub struct TrackerMonitor {
+    flags: Vec<TrackerFlags>,
+}
+
+impl TrackerMonitor {
+    /// Creates a monitor with no known sensors.
+    pub fn new() -> TrackerMonitor {
+        TrackerMonitor::default()
+    }
+
+    /// The status of each sensor at the last update, by index.
+    pub fn flags(&self) -> &[TrackerFlags] {
+        &self.flags
+    }
+
+    /// Reads the sensors of `session` and returns what changed since the last poll.
+    pub fn poll(&mut self, session: Session) -> Vec<TrackerEvent> {
+        let flags: Vec<TrackerFlags> = session.trackers().map(|t| t.flags).collect();
+        self.update(&flags)
+    }
+
+    /// Compares the status of each sensor, by index, with the last update and returns what changed.
+    ///
+    /// Sensors missing from `flags` count as disconnected.
+    pub fn update(&mut self, flags: &[TrackerFlags]) -> Vec<TrackerEvent> {
+        let mut events = Vec::new();
+        for index in 0..self.flags.len().max(flags.len()) {
+            let before = self.flags.get(index).cloned().unwrap_or_default();
+            let after = flags.get(index).cloned().unwrap_or_default();
+            let index = index as u32;
+            if before.pose_tracked && !after.pose_tracked {
+                events.push(TrackerEvent::PoseLost(index));
+            }
+            if before.connected != after.connected {
+                events.push(if after.connected { TrackerEvent::Connected(index) } else { TrackerEvent::Disconnected(index) });
+            }
+            if !before.pose_tracked && after.pose_tracked {
+                events.push(TrackerEvent::PoseTracked(index));
+            }
+        }
+        self.flags = flags.to_vec();
+        events
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_tracker_monitor() {
+    let connected = TrackerFlags::from_raw(ovrTracker_Connected as c_uint);
+    let tracked = TrackerFlags::from_raw((ovrTracker_Connected | ovrTracker_PoseTracked) as c_uint);
+    assert_eq!(tracked, TrackerFlags { connected: true, pose_tracked: true });
+
+    let mut monitor = TrackerMonitor::new();
+    assert_eq!(monitor.update(&[connected, tracked]), [TrackerEvent::Connected(0), TrackerEvent::Connected(1), TrackerEvent::PoseTracked(1)]);
+    assert_eq!(monitor.update(&[connected, tracked]), []);
+    assert_eq!(monitor.update(&[tracked, connected, tracked]), [TrackerEvent::PoseTracked(0), TrackerEvent::PoseLost(1), TrackerEvent::Connected(2), TrackerEvent::PoseTracked(2)]);
+    assert_eq!(monitor.update(&[tracked]), [TrackerEvent::Disconnected(1), TrackerEvent::PoseLost(2), TrackerEvent::Disconnected(2)]);
+    assert_eq!(monitor.flags().len(), 1);
+}
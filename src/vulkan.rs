@@ -18,6 +18,10 @@ use ::vks::{
     VkImage
 };
 
+#[cfg(feature = "trace")]
+include!(concat!(env!("OUT_DIR"), "/trace_vulkan.rs"));
+
+#[cfg(not(feature = "trace"))]
 extern "C" {
     /// Find `VkPhysicalDevice` matching `ovrGraphicsLuid`
     ///
@@ -0,0 +1,284 @@
+use ::std::error;
+use ::std::fmt;
+
+#[cfg(all(feature = "audio", windows))]
+use ::libc::c_int;
+#[cfg(all(feature = "audio", windows))]
+use ::audio::ovrAudioChannelData;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// How the samples of a WAV file are encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// Integer PCM. 8-bit samples are unsigned, wider samples are signed.
+    Pcm,
+    /// IEEE floating point.
+    Float,
+}
+
+/// The format description from the `fmt ` chunk of a WAV file.
+///
+/// For `WAVE_FORMAT_EXTENSIBLE` files the sample format is taken from the sub-format GUID.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WavFormat {
+    pub sample_format: SampleFormat,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+}
+
+/// A single channel of audio, with samples normalized to `[-1.0, 1.0]`.
+///
+/// This is the owned, portable equivalent of `ovrAudioChannelData`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioChannel {
+    pub samples: Vec<f32>,
+    /// Sample rate in Hz (e.g. 44100).
+    pub frequency: u32,
+}
+
+impl AudioChannel {
+    /// Borrows the channel as an `ovrAudioChannelData`, for passing to `ovr_GenHapticsFromAudioData`.
+    ///
+    /// The returned struct points into `self` and must not be released with `ovr_ReleaseAudioChannelData`.
+    #[cfg(all(feature = "audio", windows))]
+    pub fn as_raw(&self) -> ovrAudioChannelData {
+        ovrAudioChannelData {
+            Samples: self.samples.as_ptr(),
+            SamplesCount: self.samples.len() as c_int,
+            Frequency: self.frequency as c_int,
+        }
+    }
+}
+
+/// An error from decoding a WAV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WavError {
+    /// The data ended before the end of a header or chunk.
+    Truncated,
+    /// The data is not a RIFF WAVE file.
+    NotWave,
+    /// A required chunk (`fmt ` or `data`) is missing.
+    MissingChunk(&'static str),
+    /// The format tag (or extensible sub-format) is not PCM or IEEE float.
+    UnsupportedFormat(u16),
+    /// The bit depth is not supported for the sample format.
+    UnsupportedBitDepth(u16),
+    /// The requested channel does not exist in the file.
+    InvalidChannel { channel: usize, channels: u16 },
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WavError::Truncated => write!(fmt, "WAV data is truncated"),
+            WavError::NotWave => write!(fmt, "data is not a RIFF WAVE file"),
+            WavError::MissingChunk(id) => write!(fmt, "WAV data has no `{}` chunk", id),
+            WavError::UnsupportedFormat(tag) => write!(fmt, "unsupported WAV format tag {:#06x}", tag),
+            WavError::UnsupportedBitDepth(bits) => write!(fmt, "unsupported WAV bit depth {}", bits),
+            WavError::InvalidChannel { channel, channels } => write!(fmt, "channel {} requested from a WAV file with {} channels", channel, channels),
+        }
+    }
+}
+
+impl error::Error for WavError {}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, WavError> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).ok_or(WavError::Truncated)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, WavError> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).ok_or(WavError::Truncated)
+}
+
+/// Finds the `fmt ` and `data` chunks, returning the byte ranges of their contents.
+fn chunks(data: &[u8]) -> Result<(&[u8], &[u8]), WavError> {
+    if data.len() < 12 {
+        return Err(WavError::Truncated);
+    }
+    if &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(WavError::NotWave);
+    }
+    let mut fmt = None;
+    let mut samples = None;
+    let mut offset = 12;
+    // Compared by subtraction, as `offset` may be far past the end after an oversized chunk.
+    while offset <= data.len() && data.len() - offset >= 8 {
+        let id = &data[offset..offset + 4];
+        let size = u32_at(data, offset + 4)? as usize;
+        let start = offset + 8;
+        // Writers commonly leave the size of a trailing data chunk unpatched, so clamp it to the data.
+        let end = start.saturating_add(size).min(data.len());
+        match id {
+            b"fmt " => fmt = Some(&data[start..end]),
+            b"data" => samples = Some(&data[start..end]),
+            _ => {}
+        }
+        // Chunks are padded to an even number of bytes. A chunk running past the end of the address
+        // space is necessarily the last.
+        match start.checked_add(size).and_then(|end| end.checked_add(size & 1)) {
+            Some(next) => offset = next,
+            None => break,
+        }
+    }
+    match (fmt, samples) {
+        (Some(fmt), Some(samples)) => Ok((fmt, samples)),
+        (None, _) => Err(WavError::MissingChunk("fmt ")),
+        (_, None) => Err(WavError::MissingChunk("data")),
+    }
+}
+
+fn parse_format(fmt: &[u8]) -> Result<WavFormat, WavError> {
+    let mut tag = u16_at(fmt, 0)?;
+    if tag == WAVE_FORMAT_EXTENSIBLE {
+        // The first two bytes of the sub-format GUID hold the actual format tag.
+        tag = u16_at(fmt, 24)?;
+    }
+    let sample_format = match tag {
+        WAVE_FORMAT_PCM => SampleFormat::Pcm,
+        WAVE_FORMAT_IEEE_FLOAT => SampleFormat::Float,
+        _ => return Err(WavError::UnsupportedFormat(tag)),
+    };
+    let format = WavFormat {
+        sample_format,
+        channels: u16_at(fmt, 2)?,
+        sample_rate: u32_at(fmt, 4)?,
+        bits_per_sample: u16_at(fmt, 14)?,
+    };
+    let supported = match sample_format {
+        SampleFormat::Pcm => [8, 16, 24, 32].contains(&format.bits_per_sample),
+        SampleFormat::Float => [32, 64].contains(&format.bits_per_sample),
+    };
+    if !supported {
+        return Err(WavError::UnsupportedBitDepth(format.bits_per_sample));
+    }
+    Ok(format)
+}
+
+fn decode_sample(format: SampleFormat, bytes: &[u8]) -> f32 {
+    match (format, bytes.len()) {
+        (SampleFormat::Pcm, 1) => (bytes[0] as f32 - 128.0) / 128.0,
+        (SampleFormat::Pcm, 2) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+        (SampleFormat::Pcm, 3) => (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0,
+        (SampleFormat::Pcm, _) => (i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64 / 2147483648.0) as f32,
+        (SampleFormat::Float, 4) => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).clamp(-1.0, 1.0),
+        (SampleFormat::Float, _) => {
+            let mut b = [0; 8];
+            b.copy_from_slice(bytes);
+            (f64::from_le_bytes(b) as f32).clamp(-1.0, 1.0)
+        }
+    }
+}
+
+/// Reads the format description of a WAV file without decoding its samples.
+pub fn read_wav_format(data: &[u8]) -> Result<WavFormat, WavError> {
+    let (fmt, _) = chunks(data)?;
+    parse_format(fmt)
+}
+
+/// Reads one channel from WAV file data, converting its samples to floats in `[-1.0, 1.0]`.
+///
+/// This is a portable equivalent of `ovr_ReadWavFromBuffer`, producing the same normalized samples.
+/// In addition to the PCM 8/16/32-bit and IEEE float formats supported by LibOVR, it accepts 24-bit
+/// PCM, 64-bit float and `WAVE_FORMAT_EXTENSIBLE` headers.
+///
+/// `channel` is the index of the channel to extract, 0 for mono files. An incomplete trailing sample
+/// frame is ignored.
+///
+/// ```
+/// # use ovr_sys::wav::{read_wav, WavError};
+/// assert_eq!(read_wav(b"not a wav file", 0), Err(WavError::NotWave));
+/// ```
+pub fn read_wav(data: &[u8], channel: usize) -> Result<AudioChannel, WavError> {
+    let (fmt, samples) = chunks(data)?;
+    let format = parse_format(fmt)?;
+    if channel >= format.channels as usize {
+        return Err(WavError::InvalidChannel { channel, channels: format.channels });
+    }
+    let sample_size = format.bits_per_sample as usize / 8;
+    let frame_size = sample_size * format.channels as usize;
+    let offset = channel * sample_size;
+    let samples = samples.chunks(frame_size)
+        .filter(|frame| frame.len() == frame_size)
+        .map(|frame| decode_sample(format.sample_format, &frame[offset..offset + sample_size]))
+        .collect();
+    Ok(AudioChannel {
+        samples,
+        frequency: format.sample_rate,
+    })
+}
+
+#[cfg(test)]
+fn test_wav(tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+    let extensible = tag == WAVE_FORMAT_EXTENSIBLE;
+    let fmt_size: u32 = if extensible { 40 } else { 16 };
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(4 + 8 + fmt_size + 8 + data.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&fmt_size.to_le_bytes());
+    out.extend_from_slice(&tag.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&8000u32.to_le_bytes());
+    out.extend_from_slice(&(8000 * channels as u32 * bits as u32 / 8).to_le_bytes());
+    out.extend_from_slice(&(channels * bits / 8).to_le_bytes());
+    out.extend_from_slice(&bits.to_le_bytes());
+    if extensible {
+        out.extend_from_slice(&22u16.to_le_bytes());
+        out.extend_from_slice(&bits.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes());
+        out.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+    }
+    out.extend_from_slice(b"LIST\x03\x00\x00\x00abc\x00");
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_wav() {
+    let wav = test_wav(WAVE_FORMAT_PCM, 1, 8, &[0, 128, 192]);
+    let channel = read_wav(&wav, 0).unwrap();
+    assert_eq!(channel.frequency, 8000);
+    assert_eq!(channel.samples, [-1.0, 0.0, 0.5]);
+
+    let wav = test_wav(WAVE_FORMAT_PCM, 2, 16, &[0x00, 0x80, 0x00, 0x40, 0xff, 0x7f, 0x00, 0xc0]);
+    assert_eq!(read_wav(&wav, 0).unwrap().samples, [-1.0, 32767.0 / 32768.0]);
+    assert_eq!(read_wav(&wav, 1).unwrap().samples, [0.5, -0.5]);
+    assert_eq!(read_wav(&wav, 2), Err(WavError::InvalidChannel { channel: 2, channels: 2 }));
+
+    let wav = test_wav(WAVE_FORMAT_PCM, 1, 24, &[0x00, 0x00, 0x80, 0x00, 0x00, 0x40]);
+    assert_eq!(read_wav(&wav, 0).unwrap().samples, [-1.0, 0.5]);
+
+    let wav = test_wav(WAVE_FORMAT_PCM, 1, 32, &[0x00, 0x00, 0x00, 0xc0]);
+    assert_eq!(read_wav(&wav, 0).unwrap().samples, [-0.5]);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&0.25f32.to_le_bytes());
+    data.extend_from_slice(&(-2.0f32).to_le_bytes());
+    let wav = test_wav(WAVE_FORMAT_EXTENSIBLE, 1, 32, &data);
+    assert_eq!(read_wav_format(&wav).unwrap().sample_format, SampleFormat::Float);
+    assert_eq!(read_wav(&wav, 0).unwrap().samples, [0.25, -1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_wav_errors() {
+    assert_eq!(read_wav(b"RIFF", 0), Err(WavError::Truncated));
+    assert_eq!(read_wav(b"RIFF\x04\x00\x00\x00WAVE", 0), Err(WavError::MissingChunk("fmt ")));
+    assert_eq!(read_wav(&test_wav(0x0055, 1, 16, &[]), 0), Err(WavError::UnsupportedFormat(0x0055)));
+    assert_eq!(read_wav(&test_wav(WAVE_FORMAT_PCM, 1, 12, &[]), 0), Err(WavError::UnsupportedBitDepth(12)));
+    assert_eq!(read_wav(b"RIFF\x04\x00\x00\x00WAVEJUNK\xff\xff\xff\xff", 0), Err(WavError::MissingChunk("fmt ")));
+
+    let mut wav = test_wav(WAVE_FORMAT_PCM, 1, 8, &[0, 128]);
+    let size = wav.len() - 6;
+    wav[size..size + 4].copy_from_slice(&[0xff; 4]);
+    assert_eq!(read_wav(&wav, 0).unwrap().samples, [-1.0, 0.0]);
+}